@@ -0,0 +1,765 @@
+use serde::de::{self, DeserializeOwned, IntoDeserializer, Visitor};
+use serde::ser;
+
+use crate::config::Config;
+use crate::de::{ConfiguredDeserializer, Deserializer};
+use crate::error::Error;
+use crate::leaf::{Leaf, Sink};
+use crate::missing::{resolve_left_out, Entry, LeftOutFields, MissingDeserializer, Skipped};
+use crate::ser::{ConfiguredSerializer, MapSerializer, Serializer, TupleSerializer};
+
+/// Deserialize a struct from command-line arguments.
+///
+/// Fields are given as `--field value` or `--field=value`.  Flags are
+/// named after [`Config::arg_case`] and [`Config::arg_prefix`], so by
+/// default underscores in field names are written as dashes
+/// (`--log-level`).  A flag without a
+/// value, that is one followed by another flag or by nothing, sets a
+/// `bool` field to `true`.  Fields of type `Vec` are filled from repeated
+/// flags and every value is parsed like [`from_str`](crate::from_str).
+/// Left out flags read as `false`, as an empty `Vec` or as `None`, so the
+/// output of [`to_args`] can be read back, except that `Some(false)` and
+/// `Some` of an empty sequence read as `None`.  This takes precedence over
+/// `#[serde(default)]`, which only applies to fields of other types.
+/// Values that start with `--` must use the `--field=value` form.
+///
+/// Errors mention the flag they refer to (`Error::Arg`).
+///
+/// ```rust
+/// # #[macro_use] extern crate serde_derive;
+/// # fn main() {
+/// #[derive(Deserialize, Debug, PartialEq)]
+/// #[serde(rename_all = "lowercase")]
+/// pub enum Format {
+///     Text,
+///     Json,
+/// }
+///
+/// #[derive(Deserialize, Debug, PartialEq)]
+/// pub struct Options {
+///     format: Format,
+///     verbose: bool,
+///     include: Vec<String>,
+///     max_depth: Option<u32>,
+/// }
+///
+/// let args = ["--format=json", "--include", "src", "--verbose", "--include", "tests"];
+/// let opts: Options = serde_plain::from_args(args).unwrap();
+/// assert_eq!(opts, Options {
+///     format: Format::Json,
+///     verbose: true,
+///     include: vec!["src".into(), "tests".into()],
+///     max_depth: None,
+/// });
+/// # }
+/// ```
+pub fn from_args<T, I, S>(args: I) -> Result<T, Error>
+where
+    T: DeserializeOwned,
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+{
+    from_args_with_config(args, &Config::default())
+}
+
+/// Like [`from_args`] but with a custom configuration.
+pub fn from_args_with_config<T, I, S>(args: I, config: &Config) -> Result<T, Error>
+where
+    T: DeserializeOwned,
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+{
+    let args = group_args(args.into_iter().map(Into::into), config)?;
+    resolve_left_out(|skipped| {
+        T::deserialize(ArgsDeserializer {
+            args: &args,
+            config,
+            skipped,
+        })
+    })
+}
+
+/// All occurrences of a flag and their values.
+struct Arg {
+    flag: String,
+    values: Vec<Option<String>>,
+}
+
+/// Groups the arguments by flag in order of their first occurrence.
+fn group_args<I: Iterator<Item = String>>(args: I, config: &Config) -> Result<Vec<Arg>, Error> {
+    let prefix = &*config.arg_prefix;
+    let mut rv: Vec<Arg> = Vec::new();
+    let mut args = args.peekable();
+    while let Some(arg) = args.next() {
+        let name = match arg.strip_prefix(prefix) {
+            Some(name) if !name.is_empty() => name,
+            _ => return Err(Error::Message(format!("unexpected argument `{}`", arg))),
+        };
+        let (flag, value) = match name.find('=') {
+            Some(idx) => (
+                &arg[..prefix.len() + idx],
+                Some(name[idx + 1..].to_string()),
+            ),
+            None => {
+                let value = match args.peek() {
+                    Some(next) if !next.starts_with(prefix) => args.next(),
+                    _ => None,
+                };
+                (arg.as_str(), value)
+            }
+        };
+        match rv.iter_mut().find(|other| other.flag == flag) {
+            Some(other) => other.values.push(value),
+            None => rv.push(Arg {
+                flag: flag.to_string(),
+                values: vec![value],
+            }),
+        }
+    }
+    Ok(rv)
+}
+
+/// Returns the flag for a field.
+fn flag_for(field: &str, config: &Config) -> String {
+    format!("{}{}", config.arg_prefix, config.arg_case.apply(field))
+}
+
+/// Deserializes a struct from grouped arguments.
+struct ArgsDeserializer<'de, 'a> {
+    args: &'de [Arg],
+    config: &'a Config,
+    /// The fields not to fill in when their flags are left out.
+    skipped: &'a Skipped,
+}
+
+impl<'de, 'a> de::Deserializer<'de> for ArgsDeserializer<'de, 'a> {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, _visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        Err(Error::ImpossibleDeserialization("non-struct arguments"))
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        // unknown flags are passed on as they are so that
+        // `deny_unknown_fields` can reject them.
+        let entries: Vec<_> = self
+            .args
+            .iter()
+            .map(|arg| {
+                let field = fields
+                    .iter()
+                    .find(|field| {
+                        flag_for(field, self.config) == arg.flag
+                            || format!("{}{}", self.config.arg_prefix, field) == arg.flag
+                    })
+                    .copied()
+                    .unwrap_or(&arg.flag[self.config.arg_prefix.len()..]);
+                (field, arg)
+            })
+            .collect();
+        let config = self.config;
+        visitor
+            .visit_map(ArgsMapAccess {
+                entries: entries.into_iter(),
+                left_out: LeftOutFields::new(Some(fields), self.skipped),
+                value: None,
+                config,
+            })
+            .map_err(|error| match error {
+                Error::MissingField(field) => Error::Arg {
+                    flag: flag_for(field, config),
+                    error: Box::new(Error::MissingField(field)),
+                },
+                error => error,
+            })
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct seq tuple
+        tuple_struct map enum identifier ignored_any
+    }
+}
+
+/// Feeds the flags to the visitor of a struct.
+struct ArgsMapAccess<'de, 'a> {
+    entries: std::vec::IntoIter<(&'de str, &'de Arg)>,
+    left_out: LeftOutFields<'a>,
+    value: Option<Entry<&'de Arg>>,
+    config: &'a Config,
+}
+
+impl<'de, 'a> de::MapAccess<'de> for ArgsMapAccess<'de, 'a> {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Error>
+    where
+        K: de::DeserializeSeed<'de>,
+    {
+        let field = match self.entries.next() {
+            Some((field, arg)) => {
+                self.left_out.given(field);
+                self.value = Some(Entry::Given(arg));
+                field
+            }
+            None => match self.left_out.next() {
+                Some(field) => {
+                    self.value = Some(Entry::LeftOut(field));
+                    field
+                }
+                None => return Ok(None),
+            },
+        };
+        seed.deserialize(field.into_deserializer()).map(Some)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Error>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        match self.value.take() {
+            Some(Entry::LeftOut(field)) => {
+                let value = seed
+                    .deserialize(MissingDeserializer { field })
+                    .map_err(|error| Error::Arg {
+                        flag: flag_for(field, self.config),
+                        error: Box::new(error),
+                    })?;
+                self.left_out.filled();
+                Ok(value)
+            }
+            Some(Entry::Given(arg)) => seed
+                .deserialize(ArgValueDeserializer {
+                    arg,
+                    config: self.config,
+                })
+                .map_err(|error| match error {
+                    error @ Error::Arg { .. } => error,
+                    error => Error::Arg {
+                        flag: arg.flag.clone(),
+                        error: Box::new(error),
+                    },
+                }),
+            None => Err(Error::Message("value requested before key".into())),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.entries.len() + self.left_out.len())
+    }
+}
+
+/// Deserializes the values given for a flag.
+struct ArgValueDeserializer<'de, 'a> {
+    arg: &'de Arg,
+    config: &'a Config,
+}
+
+impl<'de, 'a> ArgValueDeserializer<'de, 'a> {
+    /// Returns the single value of the flag.
+    fn value(&self) -> Result<Option<&'de str>, Error> {
+        match *self.arg.values {
+            [ref value] => Ok(value.as_deref()),
+            _ => Err(Error::Message("given more than once".into())),
+        }
+    }
+
+    /// Returns a deserializer for the single required value of the flag.
+    fn required(&self) -> Result<ConfiguredDeserializer<'de, 'a>, Error> {
+        match self.value()? {
+            Some(value) => Ok(Deserializer::with_config(value, self.config)),
+            None => Err(Error::Message("a value is required".into())),
+        }
+    }
+}
+
+macro_rules! forward_to_value {
+    ($($func:ident)*) => {
+        $(
+            fn $func<V>(self, visitor: V) -> Result<V::Value, Error>
+            where
+                V: Visitor<'de>,
+            {
+                de::Deserializer::$func(self.required()?, visitor)
+            }
+        )*
+    };
+}
+
+impl<'de, 'a> de::Deserializer<'de> for ArgValueDeserializer<'de, 'a> {
+    type Error = Error;
+
+    forward_to_value!(deserialize_any deserialize_i8 deserialize_i16 deserialize_i32
+        deserialize_i64 deserialize_i128 deserialize_u8 deserialize_u16 deserialize_u32
+        deserialize_u64 deserialize_u128 deserialize_f32 deserialize_f64 deserialize_char
+        deserialize_str deserialize_string deserialize_bytes deserialize_byte_buf
+        deserialize_unit deserialize_map deserialize_identifier);
+
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_bool<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.value()? {
+            Some(value) => de::Deserializer::deserialize_bool(
+                Deserializer::with_config(value, self.config),
+                visitor,
+            ),
+            None => visitor.visit_bool(true),
+        }
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_unit_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        de::Deserializer::deserialize_unit_struct(self.required()?, name, visitor)
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        let items = self
+            .arg
+            .values
+            .iter()
+            .map(|value| match *value {
+                Some(ref value) => Ok(Deserializer::with_config(value, self.config)),
+                None => Err(Error::Message("a value is required".into())),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        visitor.visit_seq(ArgSeqAccess {
+            items: items.into_iter(),
+        })
+    }
+
+    fn deserialize_tuple<V>(self, len: usize, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        de::Deserializer::deserialize_tuple(self.required()?, len, visitor)
+    }
+
+    fn deserialize_tuple_struct<V>(
+        self,
+        name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        de::Deserializer::deserialize_tuple_struct(self.required()?, name, len, visitor)
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        de::Deserializer::deserialize_struct(self.required()?, name, fields, visitor)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        de::Deserializer::deserialize_enum(self.required()?, name, variants, visitor)
+    }
+}
+
+/// Feeds the values of a repeated flag to a visitor.
+struct ArgSeqAccess<'de, 'a> {
+    items: std::vec::IntoIter<ConfiguredDeserializer<'de, 'a>>,
+}
+
+impl<'de, 'a> de::SeqAccess<'de> for ArgSeqAccess<'de, 'a> {
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Error>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        match self.items.next() {
+            Some(item) => seed.deserialize(item).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.items.len())
+    }
+}
+
+/// Serialize a struct into command-line arguments.
+///
+/// This is the inverse of [`from_args`].  Every field is written as the
+/// flag followed by the value as a separate argument.  Values starting
+/// with the flag prefix, and all values if the prefix is empty, are
+/// attached with `=` instead.  Fields that are `None`, `false` or empty
+/// sequences are left out, also inside `Some`, `true` is written as a bare
+/// flag and sequences repeat the flag for every element.
+///
+/// ```rust
+/// # #[macro_use] extern crate serde_derive;
+/// # fn main() {
+/// #[derive(Serialize)]
+/// #[serde(rename_all = "lowercase")]
+/// pub enum Format {
+///     Text,
+///     Json,
+/// }
+///
+/// #[derive(Serialize)]
+/// pub struct Options {
+///     format: Format,
+///     verbose: bool,
+///     include: Vec<String>,
+///     max_depth: Option<u32>,
+/// }
+///
+/// let opts = Options {
+///     format: Format::Json,
+///     verbose: true,
+///     include: vec!["src".into(), "tests".into()],
+///     max_depth: None,
+/// };
+/// assert_eq!(serde_plain::to_args(&opts).unwrap(), vec![
+///     "--format", "json", "--verbose", "--include", "src", "--include", "tests",
+/// ]);
+/// # }
+/// ```
+pub fn to_args<T: ser::Serialize>(value: &T) -> Result<Vec<String>, Error> {
+    to_args_with_config(value, &Config::default())
+}
+
+/// Like [`to_args`] but with a custom configuration.
+pub fn to_args_with_config<T: ser::Serialize>(
+    value: &T,
+    config: &Config,
+) -> Result<Vec<String>, Error> {
+    let mut args = Vec::new();
+    value.serialize(ArgSerializer {
+        args: &mut args,
+        flag: None,
+        config,
+    })?;
+    Ok(args)
+}
+
+/// Serializes a value as the arguments of a flag.
+///
+/// Without a flag only structs can be serialized, which is how the top
+/// level value is handled.
+struct ArgSerializer<'a> {
+    args: &'a mut Vec<String>,
+    flag: Option<String>,
+    config: &'a Config,
+}
+
+impl<'a> ArgSerializer<'a> {
+    fn plain(&self) -> ConfiguredSerializer<'a> {
+        Serializer::with_config(self.config)
+    }
+}
+
+impl<'a> Sink for ArgSerializer<'a> {
+    fn push(self, value: Result<String, Error>) -> Result<(), Error> {
+        let flag = match self.flag {
+            Some(ref flag) => flag,
+            None => return Err(Error::ImpossibleSerialization("non-struct arguments")),
+        };
+        let value = value.map_err(|error| self.wrap(error))?;
+        // without a prefix every word is read as a flag.
+        let prefix = &self.config.arg_prefix;
+        if prefix.is_empty() || value.starts_with(&**prefix) {
+            self.args.push(format!("{}={}", flag, value));
+        } else {
+            self.args.push(flag.clone());
+            self.args.push(value);
+        }
+        Ok(())
+    }
+
+    fn wrap(&self, error: Error) -> Error {
+        match self.flag {
+            Some(ref flag) => Error::Arg {
+                flag: flag.clone(),
+                error: Box::new(error),
+            },
+            None => error,
+        }
+    }
+}
+
+macro_rules! serialize_as_arg {
+    ($($ty:ty => $meth:ident,)*) => {
+        $(fn $meth(self, v: $ty) -> Result<(), Error> {
+            let value = ser::Serializer::$meth(self.plain(), v);
+            self.push(value)
+        })*
+    };
+}
+
+impl<'a> ser::Serializer for ArgSerializer<'a> {
+    type Ok = ();
+    type Error = Error;
+    type SerializeSeq = ArgSeqSerializer<'a>;
+    type SerializeTuple = Leaf<ArgSerializer<'a>, TupleSerializer<'a>>;
+    type SerializeTupleStruct = Leaf<ArgSerializer<'a>, TupleSerializer<'a>>;
+    type SerializeTupleVariant = ser::Impossible<(), Error>;
+    type SerializeMap = Leaf<ArgSerializer<'a>, MapSerializer<'a>>;
+    type SerializeStruct = ArgStructSerializer<'a>;
+    type SerializeStructVariant = ser::Impossible<(), Error>;
+
+    serialize_as_arg! {
+        u8  => serialize_u8,
+        u16 => serialize_u16,
+        u32 => serialize_u32,
+        u64 => serialize_u64,
+        u128 => serialize_u128,
+        i8  => serialize_i8,
+        i16 => serialize_i16,
+        i32 => serialize_i32,
+        i64 => serialize_i64,
+        i128 => serialize_i128,
+        f32 => serialize_f32,
+        f64 => serialize_f64,
+        char => serialize_char,
+        &str => serialize_str,
+        &[u8] => serialize_bytes,
+    }
+
+    fn serialize_bool(self, v: bool) -> Result<(), Error> {
+        match self.flag {
+            Some(flag) => {
+                if v {
+                    self.args.push(flag);
+                }
+                Ok(())
+            }
+            None => Err(Error::ImpossibleSerialization("non-struct arguments")),
+        }
+    }
+
+    fn serialize_unit(self) -> Result<(), Error> {
+        let value = ser::Serializer::serialize_unit(self.plain());
+        self.push(value)
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<(), Error> {
+        let value = ser::Serializer::serialize_unit_struct(self.plain(), name);
+        self.push(value)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+    ) -> Result<(), Error> {
+        let value =
+            ser::Serializer::serialize_unit_variant(self.plain(), name, variant_index, variant);
+        self.push(value)
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + ser::Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + ser::Serialize>(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        let value = ser::Serializer::serialize_newtype_variant(
+            self.plain(),
+            name,
+            variant_index,
+            variant,
+            value,
+        );
+        self.push(value)
+    }
+
+    fn serialize_none(self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn serialize_some<T: ?Sized + ser::Serialize>(self, value: &T) -> Result<(), Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Error> {
+        Ok(ArgSeqSerializer { arg: self })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Error> {
+        let inner = ser::Serializer::serialize_tuple(self.plain(), len);
+        Leaf::new(self, inner)
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Error> {
+        let inner = ser::Serializer::serialize_tuple_struct(self.plain(), name, len);
+        Leaf::new(self, inner)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Error> {
+        Err(self.wrap(Error::ImpossibleSerialization("tuple variant")))
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, Error> {
+        let inner = ser::Serializer::serialize_map(self.plain(), len);
+        Leaf::new(self, inner)
+    }
+
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, Error> {
+        if self.flag.is_none() {
+            return Ok(ArgStructSerializer::Fields(self));
+        }
+        let inner = ser::Serializer::serialize_struct(self.plain(), name, len);
+        Leaf::new(self, inner).map(|leaf| ArgStructSerializer::Leaf(Box::new(leaf)))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Error> {
+        Err(self.wrap(Error::ImpossibleSerialization("struct variant")))
+    }
+}
+
+/// Repeats a flag for every element of a sequence.
+struct ArgSeqSerializer<'a> {
+    arg: ArgSerializer<'a>,
+}
+
+impl<'a> ser::SerializeSeq for ArgSeqSerializer<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: ?Sized + ser::Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        value.serialize(ArgSerializer {
+            args: &mut *self.arg.args,
+            flag: self.arg.flag.clone(),
+            config: self.arg.config,
+        })
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+/// Serializes the fields of the top level struct as flags, or a nested
+/// struct as the value of a single flag.
+enum ArgStructSerializer<'a> {
+    Fields(ArgSerializer<'a>),
+    Leaf(Box<Leaf<ArgSerializer<'a>, MapSerializer<'a>>>),
+}
+
+impl<'a> ser::SerializeStruct for ArgStructSerializer<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + ser::Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        match *self {
+            ArgStructSerializer::Fields(ref mut arg) => value.serialize(ArgSerializer {
+                args: &mut *arg.args,
+                flag: Some(flag_for(key, arg.config)),
+                config: arg.config,
+            }),
+            ArgStructSerializer::Leaf(ref mut leaf) => leaf.serialize_field(key, value),
+        }
+    }
+
+    fn end(self) -> Result<(), Error> {
+        match self {
+            ArgStructSerializer::Fields(_) => Ok(()),
+            ArgStructSerializer::Leaf(leaf) => leaf.end(),
+        }
+    }
+}
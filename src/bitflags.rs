@@ -0,0 +1,95 @@
+//! Serializing `bitflags` types as sets of flag names.
+//!
+//! The serde support of `bitflags` writes its own `READ | EXEC` format.
+//! The functions in this module are meant to be used with
+//! `#[serde(with = "serde_plain::bitflags")]` and treat the flags as a
+//! sequence of their names in lowercase instead, so that a configured
+//! [`seq_separator`](crate::Config::seq_separator) joins them and the
+//! duplicate, sorting and empty set options apply like for other
+//! sequences.  Names are matched ignoring case when parsing.
+//!
+//! This module requires the `bitflags` feature.
+//!
+//! ```rust
+//! # #[macro_use] extern crate serde_derive;
+//! use serde_plain::Config;
+//! # fn main() {
+//!
+//! bitflags::bitflags! {
+//!     #[derive(Debug, PartialEq)]
+//!     pub struct Perms: u8 {
+//!         const READ = 1;
+//!         const WRITE = 2;
+//!         const EXEC = 4;
+//!     }
+//! }
+//!
+//! #[derive(Serialize, Deserialize, Debug, PartialEq)]
+//! pub struct Mode(#[serde(with = "serde_plain::bitflags")] Perms);
+//!
+//! let config = Config::new().seq_separator("|");
+//! let mode = Mode(Perms::READ | Perms::EXEC);
+//! assert_eq!(serde_plain::to_string_with_config(&mode, &config).unwrap(), "read|exec");
+//! assert_eq!(serde_plain::from_str_with_config::<Mode>("read|exec", &config).unwrap(), mode);
+//! # }
+//! ```
+use std::fmt;
+use std::marker::PhantomData;
+
+use ::bitflags::Flags;
+use serde::{de, ser};
+
+/// Serializes the flags as a sequence of their names.
+///
+/// Bits that do not belong to a named flag cannot be represented and
+/// fail to serialize.
+pub fn serialize<F, S>(flags: &F, serializer: S) -> Result<S::Ok, S::Error>
+where
+    F: Flags,
+    S: ser::Serializer,
+{
+    if F::from_bits(flags.bits()).is_none() {
+        return Err(ser::Error::custom("cannot represent unnamed bits of flags"));
+    }
+    serializer.collect_seq(
+        flags
+            .iter_names()
+            .map(|(name, _)| name.to_ascii_lowercase()),
+    )
+}
+
+/// Deserializes the flags from a sequence of their names.
+pub fn deserialize<'de, F, D>(deserializer: D) -> Result<F, D::Error>
+where
+    F: Flags,
+    D: de::Deserializer<'de>,
+{
+    deserializer.deserialize_seq(FlagsVisitor(PhantomData))
+}
+
+struct FlagsVisitor<F>(PhantomData<F>);
+
+impl<'de, F: Flags> de::Visitor<'de> for FlagsVisitor<F> {
+    type Value = F;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a sequence of flag names")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<F, A::Error>
+    where
+        A: de::SeqAccess<'de>,
+    {
+        let mut flags = F::empty();
+        while let Some(name) = seq.next_element::<std::borrow::Cow<'de, str>>()? {
+            match F::FLAGS
+                .iter()
+                .find(|flag| !flag.name().is_empty() && flag.name().eq_ignore_ascii_case(&name))
+            {
+                Some(flag) => flags.insert(F::from_bits_retain(flag.value().bits())),
+                None => return Err(de::Error::custom(format!("unknown flag `{}`", name))),
+            }
+        }
+        Ok(flags)
+    }
+}
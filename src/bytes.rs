@@ -0,0 +1,104 @@
+use crate::config::ByteEncoding;
+use crate::error::Error;
+
+const STANDARD: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const URL_SAFE: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Encodes bytes as text.
+pub(crate) fn encode(data: &[u8], encoding: ByteEncoding) -> String {
+    match encoding {
+        ByteEncoding::Hex => encode_hex(data, b"0123456789abcdef"),
+        ByteEncoding::UpperHex => encode_hex(data, b"0123456789ABCDEF"),
+        ByteEncoding::Base64 => encode_base64(data, STANDARD, true),
+        ByteEncoding::Base64NoPad => encode_base64(data, STANDARD, false),
+        ByteEncoding::Base64Url => encode_base64(data, URL_SAFE, true),
+        ByteEncoding::Base64UrlNoPad => encode_base64(data, URL_SAFE, false),
+    }
+}
+
+/// Decodes bytes from text.
+pub(crate) fn decode(input: &str, encoding: ByteEncoding) -> Result<Vec<u8>, Error> {
+    match encoding {
+        ByteEncoding::Hex | ByteEncoding::UpperHex => decode_hex(input),
+        ByteEncoding::Base64 => decode_base64(input, STANDARD, true),
+        ByteEncoding::Base64NoPad => decode_base64(input, STANDARD, false),
+        ByteEncoding::Base64Url => decode_base64(input, URL_SAFE, true),
+        ByteEncoding::Base64UrlNoPad => decode_base64(input, URL_SAFE, false),
+    }
+    .map_err(|msg| Error::Parse("bytes", msg))
+}
+
+fn encode_hex(data: &[u8], digits: &[u8; 16]) -> String {
+    let mut rv = String::with_capacity(data.len() * 2);
+    for &byte in data {
+        rv.push(digits[(byte >> 4) as usize] as char);
+        rv.push(digits[(byte & 0xf) as usize] as char);
+    }
+    rv
+}
+
+fn decode_hex(input: &str) -> Result<Vec<u8>, String> {
+    if input.len() % 2 != 0 {
+        return Err("odd number of hex digits".into());
+    }
+    let digit = |c: u8| {
+        (c as char)
+            .to_digit(16)
+            .ok_or_else(|| format!("invalid hex digit `{}`", c as char))
+    };
+    input
+        .as_bytes()
+        .chunks(2)
+        .map(|pair| Ok((digit(pair[0])? << 4 | digit(pair[1])?) as u8))
+        .collect()
+}
+
+fn encode_base64(data: &[u8], alphabet: &[u8; 64], pad: bool) -> String {
+    let mut rv = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let n = (chunk[0] as u32) << 16
+            | (chunk.get(1).copied().unwrap_or(0) as u32) << 8
+            | chunk.get(2).copied().unwrap_or(0) as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                rv.push(alphabet[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else if pad {
+                rv.push('=');
+            }
+        }
+    }
+    rv
+}
+
+fn decode_base64(input: &str, alphabet: &[u8; 64], pad: bool) -> Result<Vec<u8>, String> {
+    let data = if pad {
+        let data = input.trim_end_matches('=');
+        if input.len() % 4 != 0 || input.len() - data.len() > 2 {
+            return Err("invalid base64 padding".into());
+        }
+        data
+    } else {
+        input
+    };
+    if data.len() % 4 == 1 {
+        return Err("invalid base64 length".into());
+    }
+
+    let mut rv = Vec::with_capacity(data.len() * 3 / 4);
+    let mut buf = 0u32;
+    let mut bits = 0;
+    for c in data.bytes() {
+        let value = alphabet
+            .iter()
+            .position(|&x| x == c)
+            .ok_or_else(|| format!("invalid base64 character `{}`", c as char))?;
+        buf = buf << 6 | value as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            rv.push((buf >> bits) as u8);
+            buf &= (1 << bits) - 1;
+        }
+    }
+    Ok(rv)
+}
@@ -0,0 +1,130 @@
+use serde::{Deserialize, Serialize};
+
+use crate::config::{Config, ParseMode};
+use crate::de::from_str_with_config;
+use crate::error::{Error, NonCanonicalKind};
+use crate::ser::to_string_with_config;
+
+/// Parses the input as `T` and returns its canonical representation
+/// together with whether the input was already canonical.
+///
+/// The canonical representation is what [`to_string`](crate::to_string)
+/// produces for the parsed value.  This can be used to normalize values
+/// before storing them and to warn about legacy spellings:
+///
+/// ```rust
+/// # #[macro_use] extern crate serde_derive;
+/// # fn main() {
+/// #[derive(Serialize, Deserialize)]
+/// #[serde(rename_all = "lowercase")]
+/// pub enum Env {
+///     #[serde(alias = "prod")]
+///     Production,
+///     Staging,
+/// }
+///
+/// assert_eq!(
+///     serde_plain::canonicalize::<Env>("prod").unwrap(),
+///     ("production".to_string(), false)
+/// );
+/// assert_eq!(
+///     serde_plain::canonicalize::<u32>("42").unwrap(),
+///     ("42".to_string(), true)
+/// );
+/// # }
+/// ```
+pub fn canonicalize<'a, T>(input: &'a str) -> Result<(String, bool), Error>
+where
+    T: Deserialize<'a> + Serialize,
+{
+    canonicalize_with_config::<T>(input, &Config::default())
+}
+
+/// Like [`canonicalize`] but with a custom configuration.
+///
+/// In [`Canonical`](ParseMode::Canonical) parse mode, input that is not
+/// already canonical fails with [`Error::NonCanonical`].  Unlike parsing
+/// with [`from_str_with_config`](crate::from_str_with_config) this also
+/// catches `#[serde(alias)]` spellings as the value is serialized again.
+pub fn canonicalize_with_config<'a, T>(
+    input: &'a str,
+    config: &Config,
+) -> Result<(String, bool), Error>
+where
+    T: Deserialize<'a> + Serialize,
+{
+    let canonical = to_string_with_config(&from_str_with_config::<T>(input, config)?, config)?;
+    if config.parse_mode == ParseMode::Canonical {
+        check(input, &canonical)?;
+    }
+    let is_canonical = canonical == input;
+    Ok((canonical, is_canonical))
+}
+
+/// Checks that the input is a valid and canonical representation of `T`.
+///
+/// Input that parses but is not spelled canonically fails with
+/// [`Error::NonCanonical`] which reports the canonical spelling and what
+/// kind of deviation was found.  This is useful to warn about deprecated
+/// aliases:
+///
+/// ```rust
+/// # #[macro_use] extern crate serde_derive;
+/// use serde_plain::{Error, NonCanonicalKind};
+/// # fn main() {
+///
+/// #[derive(Serialize, Deserialize)]
+/// #[serde(rename_all = "lowercase")]
+/// pub enum Env {
+///     #[serde(alias = "prod")]
+///     Production,
+///     Staging,
+/// }
+///
+/// assert!(serde_plain::validate::<Env>("production").is_ok());
+/// match serde_plain::validate::<Env>("prod") {
+///     Err(Error::NonCanonical { kind: NonCanonicalKind::Alias, expected, .. }) => {
+///         assert_eq!(expected, "production");
+///     }
+///     _ => unreachable!(),
+/// }
+/// # }
+/// ```
+pub fn validate<'a, T>(input: &'a str) -> Result<(), Error>
+where
+    T: Deserialize<'a> + Serialize,
+{
+    validate_with_config::<T>(input, &Config::default())
+}
+
+/// Like [`validate`] but with a custom configuration.
+pub fn validate_with_config<'a, T>(input: &'a str, config: &Config) -> Result<(), Error>
+where
+    T: Deserialize<'a> + Serialize,
+{
+    check(input, &canonicalize_with_config::<T>(input, config)?.0)
+}
+
+/// Fails with [`Error::NonCanonical`] unless `input` is `canonical`.
+fn check(input: &str, canonical: &str) -> Result<(), Error> {
+    if canonical == input {
+        return Ok(());
+    }
+    Err(Error::NonCanonical {
+        found: input.to_string(),
+        kind: classify(input, canonical),
+        expected: canonical.to_string(),
+    })
+}
+
+fn classify(found: &str, expected: &str) -> NonCanonicalKind {
+    if found.trim() == expected {
+        NonCanonicalKind::Whitespace
+    } else if found.eq_ignore_ascii_case(expected) {
+        NonCanonicalKind::Case
+    } else if found.trim().parse::<f64>().is_ok() && expected.parse::<f64>().is_ok() {
+        NonCanonicalKind::Number
+    } else {
+        NonCanonicalKind::Alias
+    }
+}
@@ -0,0 +1,661 @@
+use std::borrow::Cow;
+
+use crate::error::Error;
+use crate::pattern::Pattern;
+
+/// Controls how values are turned into plain text and back.
+///
+/// The default configuration behaves exactly like [`to_string`](crate::to_string)
+/// and [`from_str`](crate::from_str).  A configuration is passed to
+/// [`to_string_with_config`](crate::to_string_with_config) and
+/// [`from_str_with_config`](crate::from_str_with_config) or directly to
+/// [`Serializer::with_config`](crate::Serializer::with_config) and
+/// [`Deserializer::with_config`](crate::Deserializer::with_config).
+///
+/// ```rust
+/// use serde_plain::{Config, FloatFormat};
+///
+/// let config = Config::new().float_format(FloatFormat::Fixed(2));
+/// assert_eq!(serde_plain::to_string_with_config(&1.5f64, &config).unwrap(), "1.50");
+/// ```
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub(crate) float_format: FloatFormat,
+    pub(crate) allow_non_finite: bool,
+    pub(crate) nan_repr: Option<String>,
+    pub(crate) infinity_repr: Option<String>,
+    pub(crate) neg_infinity_repr: Option<String>,
+    pub(crate) none_repr: Option<String>,
+    pub(crate) parse_mode: ParseMode,
+    pub(crate) byte_encoding: Option<ByteEncoding>,
+    pub(crate) unit_struct_names: bool,
+    pub(crate) serialize_variant_index: bool,
+    pub(crate) accept_variant_index: bool,
+    pub(crate) variant_prefixes: bool,
+    pub(crate) seq_separator: Option<String>,
+    pub(crate) seq_unique: bool,
+    pub(crate) seq_sorted: bool,
+    pub(crate) tuple_separator: Option<String>,
+    pub(crate) key_value_separator: Cow<'static, str>,
+    pub(crate) quoting: Quoting,
+    pub(crate) patterns: Vec<(String, Pattern)>,
+    pub(crate) arg_prefix: Cow<'static, str>,
+    pub(crate) arg_case: ArgCase,
+}
+
+/// The notation used to serialize floating point numbers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FloatFormat {
+    /// The shortest representation that round-trips.  Integral values are
+    /// written without a fractional part (`1`, `0.5`).
+    #[default]
+    Shortest,
+    /// Like [`Shortest`](FloatFormat::Shortest) but integral values keep a
+    /// trailing `.0` (`1.0`, `0.5`).
+    Decimal,
+    /// A fixed number of digits after the decimal point (`1.50`).
+    Fixed(usize),
+    /// Scientific notation (`1.5e0`).
+    Scientific,
+}
+
+/// How strictly the deserializer treats its input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ParseMode {
+    /// The input is handed to the parser as-is.  Leading or trailing
+    /// whitespace is an error, but alternative spellings the parser
+    /// understands (`+42`, `042`, `1e3`) are accepted.
+    #[default]
+    Exact,
+    /// Like [`Exact`](ParseMode::Exact) but surrounding whitespace is
+    /// removed before parsing.
+    Trimmed,
+    /// Only input that the [`Serializer`](crate::Serializer) would produce
+    /// byte-for-byte from the parsed value is accepted.  This guarantees
+    /// lossless round-trips.
+    ///
+    /// Spellings that a type accepts through `#[serde(alias)]` are not
+    /// visible to the deserializer.  They are rejected by
+    /// [`canonicalize_with_config`](crate::canonicalize_with_config) which
+    /// serializes the parsed value again.
+    Canonical,
+}
+
+/// The text encoding used for byte strings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ByteEncoding {
+    /// Lowercase hexadecimal (`deadbeef`).
+    Hex,
+    /// Uppercase hexadecimal (`DEADBEEF`).
+    UpperHex,
+    /// Standard base64 with padding (`3q2+7w==`).
+    Base64,
+    /// Standard base64 without padding (`3q2+7w`).
+    Base64NoPad,
+    /// URL-safe base64 with padding (`3q2-7w==`).
+    Base64Url,
+    /// URL-safe base64 without padding (`3q2-7w`).
+    Base64UrlNoPad,
+}
+
+/// How strings are quoted within delimited values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Quoting {
+    /// Strings are never quoted.  Values containing a separator cannot be
+    /// serialized.
+    #[default]
+    Never,
+    /// Strings are wrapped in double quotes when needed.  Within quotes
+    /// `\"`, `\\`, `\n`, `\r`, `\t`, `\0` and `\u{...}` escapes are
+    /// understood.
+    DoubleQuotes,
+    /// Strings are written as POSIX shell words: bare when they only
+    /// contain safe characters, otherwise in single quotes with `'` written
+    /// as `'\''`.  When parsing, single quotes, double quotes with
+    /// backslash escapes and backslashes outside of quotes are understood.
+    Shell,
+}
+
+/// How field names are written as command-line flags.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ArgCase {
+    /// Underscores become dashes (`--max-depth`).
+    #[default]
+    Kebab,
+    /// Dashes become underscores (`--max_depth`).
+    Snake,
+    /// Field names are used as they are.
+    Verbatim,
+}
+
+impl ArgCase {
+    /// Converts a field name into the name of a flag.
+    pub(crate) fn apply(self, field: &str) -> String {
+        match self {
+            ArgCase::Kebab => field.replace('_', "-"),
+            ArgCase::Snake => field.replace('-', "_"),
+            ArgCase::Verbatim => field.to_string(),
+        }
+    }
+}
+
+/// The default configuration.  It is borrowed by [`Serializer`] and
+/// [`Deserializer::new`].
+///
+/// [`Serializer`]: crate::Serializer
+/// [`Deserializer::new`]: crate::Deserializer::new
+pub(crate) static DEFAULT: Config = Config {
+    float_format: FloatFormat::Shortest,
+    allow_non_finite: true,
+    nan_repr: None,
+    infinity_repr: None,
+    neg_infinity_repr: None,
+    none_repr: None,
+    parse_mode: ParseMode::Exact,
+    byte_encoding: None,
+    unit_struct_names: false,
+    serialize_variant_index: false,
+    accept_variant_index: false,
+    variant_prefixes: false,
+    seq_separator: None,
+    seq_unique: false,
+    seq_sorted: false,
+    tuple_separator: None,
+    key_value_separator: Cow::Borrowed("="),
+    quoting: Quoting::Never,
+    patterns: Vec::new(),
+    arg_prefix: Cow::Borrowed("--"),
+    arg_case: ArgCase::Kebab,
+};
+
+impl Default for Config {
+    fn default() -> Config {
+        DEFAULT.clone()
+    }
+}
+
+impl Config {
+    /// Creates the default configuration.
+    pub fn new() -> Config {
+        Config::default()
+    }
+
+    /// Sets the notation used for serializing floats.
+    ///
+    /// Deserialization accepts every notation regardless of this setting.
+    pub fn float_format(mut self, format: FloatFormat) -> Config {
+        self.float_format = format;
+        self
+    }
+
+    /// Controls if `NaN` and the infinities are accepted.
+    ///
+    /// This is enabled by default.  When disabled, serializing or
+    /// deserializing a non-finite float fails.
+    pub fn allow_non_finite(mut self, yes: bool) -> Config {
+        self.allow_non_finite = yes;
+        self
+    }
+
+    /// Sets the spelling of `NaN`.
+    ///
+    /// The spelling is used when serializing and is accepted in addition
+    /// to the spellings of [`f64::from_str`](std::str::FromStr) when
+    /// deserializing.
+    pub fn nan_repr(mut self, repr: &str) -> Config {
+        self.nan_repr = Some(repr.to_string());
+        self
+    }
+
+    /// Sets the spelling of positive infinity (for instance `Infinity`).
+    ///
+    /// This works like [`nan_repr`](Config::nan_repr).
+    pub fn infinity_repr(mut self, repr: &str) -> Config {
+        self.infinity_repr = Some(repr.to_string());
+        self
+    }
+
+    /// Sets the spelling of negative infinity (for instance `-Infinity`).
+    ///
+    /// This works like [`nan_repr`](Config::nan_repr).
+    pub fn neg_infinity_repr(mut self, repr: &str) -> Config {
+        self.neg_infinity_repr = Some(repr.to_string());
+        self
+    }
+
+    /// Sets the representation of `None` and `()`.
+    ///
+    /// By default both are represented by the empty string which means
+    /// that `Some("")` cannot be told apart from `None`.  With a sentinel
+    /// such as `-` or `null` this ambiguity goes away:
+    ///
+    /// ```rust
+    /// use serde_plain::Config;
+    ///
+    /// let config = Config::new().none_repr("-");
+    /// let value: Option<String> = serde_plain::from_str_with_config("", &config).unwrap();
+    /// assert_eq!(value, Some("".to_string()));
+    /// assert_eq!(serde_plain::to_string_with_config(&None::<String>, &config).unwrap(), "-");
+    /// ```
+    ///
+    /// A `Some` value whose representation consists only of repetitions of
+    /// the sentinel gets one more sentinel prepended.  This keeps values that
+    /// look like the sentinel as well as nested options such as
+    /// `Option<Option<T>>` distinguishable: with `-` as sentinel `None` is
+    /// `-`, `Some(None)` is `--` and `Some(Some("-"))` is `---`.
+    pub fn none_repr(mut self, repr: &str) -> Config {
+        self.none_repr = if repr.is_empty() {
+            None
+        } else {
+            Some(repr.to_string())
+        };
+        self
+    }
+
+    /// Sets how strictly input is parsed.
+    ///
+    /// ```rust
+    /// use serde_plain::{Config, ParseMode};
+    ///
+    /// let lenient = Config::new().parse_mode(ParseMode::Trimmed);
+    /// assert_eq!(serde_plain::from_str_with_config::<u32>(" 42 ", &lenient).unwrap(), 42);
+    ///
+    /// let strict = Config::new().parse_mode(ParseMode::Canonical);
+    /// assert!(serde_plain::from_str_with_config::<u32>("042", &strict).is_err());
+    /// ```
+    pub fn parse_mode(mut self, mode: ParseMode) -> Config {
+        self.parse_mode = mode;
+        self
+    }
+
+    /// Sets the encoding for byte strings.
+    ///
+    /// By default bytes cannot be serialized or deserialized.  With an
+    /// encoding set, types that serialize as bytes (for instance through
+    /// `serde_bytes`) are written and read in that encoding.  Hex input
+    /// is accepted in either case unless the parse mode is
+    /// [`Canonical`](ParseMode::Canonical).
+    pub fn byte_encoding(mut self, encoding: ByteEncoding) -> Config {
+        self.byte_encoding = Some(encoding);
+        self
+    }
+
+    /// Represents unit structs by their name instead of like `()`.
+    ///
+    /// By default a unit struct such as `struct Auto;` is written the same
+    /// way as `()` (the empty string unless [`none_repr`](Config::none_repr)
+    /// is set).  With this enabled it is written as `Auto` and only `Auto`
+    /// is accepted when deserializing.
+    ///
+    /// Inside `#[serde(untagged)]` enums serde buffers the input without
+    /// knowing the target type.  When a sentinel is set with
+    /// [`none_repr`](Config::none_repr) it is buffered as unit, so a
+    /// variant like `Auto(Auto)` matches it and strings do not.  Without a
+    /// sentinel the empty string stays a string.  The struct name is not
+    /// known there, so this option does not make such a variant match the
+    /// name.
+    pub fn unit_struct_names(mut self, yes: bool) -> Config {
+        self.unit_struct_names = yes;
+        self
+    }
+
+    /// Serializes unit variants by their index instead of their name.
+    ///
+    /// The index is the position of the variant in the enum declaration.
+    ///
+    /// ```rust
+    /// # #[macro_use] extern crate serde_derive;
+    /// use serde_plain::Config;
+    /// # fn main() {
+    ///
+    /// #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    /// pub enum Level {
+    ///     Low,
+    ///     Mid,
+    ///     High,
+    /// }
+    ///
+    /// let config = Config::new()
+    ///     .serialize_variant_index(true)
+    ///     .accept_variant_index(true);
+    /// assert_eq!(serde_plain::to_string_with_config(&Level::High, &config).unwrap(), "2");
+    /// assert_eq!(serde_plain::from_str_with_config::<Level>("1", &config).unwrap(), Level::Mid);
+    /// # }
+    /// ```
+    pub fn serialize_variant_index(mut self, yes: bool) -> Config {
+        self.serialize_variant_index = yes;
+        self
+    }
+
+    /// Accepts the index of a unit variant in place of its name.
+    ///
+    /// Names continue to be accepted and take precedence, so a variant
+    /// renamed to `"0"` is not shadowed by the first variant.  The index
+    /// refers to the list of variants that can be deserialized, so variants
+    /// marked with `#[serde(skip_deserializing)]` shift the indexes of the
+    /// variants that follow them.
+    pub fn accept_variant_index(mut self, yes: bool) -> Config {
+        self.accept_variant_index = yes;
+        self
+    }
+
+    /// Accepts any unambiguous prefix of a variant name.
+    ///
+    /// This is useful for interactive tools where users can abbreviate
+    /// values.  A prefix shared by multiple variants fails with
+    /// [`Error::AmbiguousVariant`] which lists the candidates.
+    ///
+    /// ```rust
+    /// # #[macro_use] extern crate serde_derive;
+    /// use serde_plain::Config;
+    /// # fn main() {
+    ///
+    /// #[derive(Deserialize, Debug, PartialEq)]
+    /// #[serde(rename_all = "lowercase")]
+    /// pub enum Channel {
+    ///     Production,
+    ///     Staging,
+    ///     Stable,
+    /// }
+    ///
+    /// let config = Config::new().variant_prefixes(true);
+    /// assert_eq!(serde_plain::from_str_with_config::<Channel>("prod", &config).unwrap(), Channel::Production);
+    /// assert!(serde_plain::from_str_with_config::<Channel>("st", &config).is_err());
+    /// # }
+    /// ```
+    pub fn variant_prefixes(mut self, yes: bool) -> Config {
+        self.variant_prefixes = yes;
+        self
+    }
+
+    /// Enables sequences by joining their elements with a separator.
+    ///
+    /// By default sequences cannot be serialized.  With a separator, a
+    /// `Vec`, `BTreeSet` or similar collection is written as its elements
+    /// joined by the separator and an empty collection is written as the
+    /// empty string.  This is useful for sets of flags:
+    ///
+    /// ```rust
+    /// # #[macro_use] extern crate serde_derive;
+    /// use serde_plain::Config;
+    /// # fn main() {
+    ///
+    /// #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    /// #[serde(rename_all = "lowercase")]
+    /// pub enum Perm {
+    ///     Read,
+    ///     Write,
+    ///     Exec,
+    /// }
+    ///
+    /// let config = Config::new().seq_separator("|");
+    /// let perms = vec![Perm::Read, Perm::Exec];
+    /// assert_eq!(serde_plain::to_string_with_config(&perms, &config).unwrap(), "read|exec");
+    /// assert_eq!(serde_plain::from_str_with_config::<Vec<Perm>>("read|exec", &config).unwrap(), perms);
+    /// # }
+    /// ```
+    ///
+    /// Elements must not contain the separator.
+    ///
+    /// `bitflags` types write their own `READ | EXEC` format unless they are
+    /// serialized through the `serde_plain::bitflags` module, which requires
+    /// the `bitflags` feature.
+    pub fn seq_separator(mut self, separator: &str) -> Config {
+        self.seq_separator = Some(separator.to_string());
+        self
+    }
+
+    /// Rejects sequences that contain the same element twice.
+    ///
+    /// When deserializing, elements are compared after they are parsed, so
+    /// `1|01` is rejected for numbers and a variant name together with its
+    /// index or prefix is rejected for enums.
+    pub fn seq_unique(mut self, yes: bool) -> Config {
+        self.seq_unique = yes;
+        self
+    }
+
+    /// Sorts the elements of sequences when serializing.
+    ///
+    /// Unit variants are ordered by their position in the enum, integers
+    /// and floats by their value and everything else, including strings
+    /// that look like numbers, by its text.  The order is taken from the
+    /// type of the elements rather than from their text.  This gives a
+    /// canonical output order for sets stored in a `Vec` or `HashSet`.
+    pub fn seq_sorted(mut self, yes: bool) -> Config {
+        self.seq_sorted = yes;
+        self
+    }
+
+    /// Sets the separator between keys and values of maps and structs.
+    ///
+    /// Maps and structs are supported when a
+    /// [`seq_separator`](Config::seq_separator) is set.  They are written
+    /// as entries of the form `key=value` joined by the sequence separator.
+    /// The default separator between keys and values is `=`.
+    ///
+    /// ```rust
+    /// # #[macro_use] extern crate serde_derive;
+    /// use serde_plain::Config;
+    /// # fn main() {
+    ///
+    /// #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    /// pub struct Limits {
+    ///     min: u32,
+    ///     max: u32,
+    /// }
+    ///
+    /// let config = Config::new().seq_separator(",").key_value_separator(":");
+    /// let limits = Limits { min: 1, max: 10 };
+    /// assert_eq!(serde_plain::to_string_with_config(&limits, &config).unwrap(), "min:1,max:10");
+    /// assert_eq!(serde_plain::from_str_with_config::<Limits>("max:10,min:1", &config).unwrap(), limits);
+    /// # }
+    /// ```
+    pub fn key_value_separator(mut self, separator: &str) -> Config {
+        self.key_value_separator = Cow::Owned(separator.to_string());
+        self
+    }
+
+    /// Sets how strings are quoted.
+    ///
+    /// With [`Quoting::DoubleQuotes`] strings that contain a separator,
+    /// quotes, control characters or surrounding whitespace, as well as
+    /// empty strings, are wrapped in double quotes.  When deserializing,
+    /// separators within quotes are ignored and quoted values are unescaped.
+    ///
+    /// ```rust
+    /// use serde_plain::{Config, Quoting};
+    ///
+    /// let config = Config::new().seq_separator(",").quoting(Quoting::DoubleQuotes);
+    /// let value = vec!["a,b", "c"];
+    /// assert_eq!(serde_plain::to_string_with_config(&value, &config).unwrap(), r#""a,b",c"#);
+    /// assert_eq!(serde_plain::from_str_with_config::<Vec<String>>(r#""a,b",c"#, &config).unwrap(), value);
+    /// ```
+    ///
+    /// With [`Quoting::Shell`] values are written as shell words.  Unless a
+    /// sequence separator is set, sequences become space-separated words
+    /// and any run of whitespace separates words when parsing:
+    ///
+    /// ```rust
+    /// use serde_plain::{Config, Quoting};
+    ///
+    /// let config = Config::new().quoting(Quoting::Shell);
+    /// let value = vec!["echo", "it's here"];
+    /// assert_eq!(serde_plain::to_string_with_config(&value, &config).unwrap(), r#"echo 'it'\''s here'"#);
+    /// assert_eq!(serde_plain::from_str_with_config::<Vec<String>>(r#"echo  "it's" here"#, &config).unwrap(), vec!["echo", "it's", "here"]);
+    /// ```
+    pub fn quoting(mut self, quoting: Quoting) -> Config {
+        self.quoting = quoting;
+        self
+    }
+
+    /// Enables tuples and tuple structs and sets the separator between
+    /// their fields.
+    ///
+    /// The number of fields must match exactly when parsing.
+    ///
+    /// ```rust
+    /// # #[macro_use] extern crate serde_derive;
+    /// use serde_plain::Config;
+    /// # fn main() {
+    ///
+    /// #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    /// pub struct Rgb(u8, u8, u8);
+    ///
+    /// let config = Config::new().tuple_separator(",");
+    /// assert_eq!(serde_plain::to_string_with_config(&Rgb(10, 20, 30), &config).unwrap(), "10,20,30");
+    /// assert_eq!(serde_plain::from_str_with_config::<Rgb>("10,20,30", &config).unwrap(), Rgb(10, 20, 30));
+    /// assert!(serde_plain::from_str_with_config::<Rgb>("10,20", &config).is_err());
+    /// # }
+    /// ```
+    pub fn tuple_separator(mut self, separator: &str) -> Config {
+        self.tuple_separator = Some(separator.to_string());
+        self
+    }
+
+    /// Sets the prefix of command-line flags.  The default is `--`.  With
+    /// an empty prefix every argument is a flag, so values are always
+    /// attached with `=` (`max_depth=2`).
+    ///
+    /// ```rust
+    /// # #[macro_use] extern crate serde_derive;
+    /// use serde_plain::{ArgCase, Config};
+    /// # fn main() {
+    ///
+    /// #[derive(Serialize)]
+    /// pub struct Options {
+    ///     max_depth: u32,
+    /// }
+    ///
+    /// let config = Config::new().arg_prefix("-").arg_case(ArgCase::Snake);
+    /// let args = serde_plain::to_args_with_config(&Options { max_depth: 2 }, &config).unwrap();
+    /// assert_eq!(args, vec!["-max_depth", "2"]);
+    /// # }
+    /// ```
+    pub fn arg_prefix(mut self, prefix: &str) -> Config {
+        self.arg_prefix = Cow::Owned(prefix.to_string());
+        self
+    }
+
+    /// Sets how field names are written as command-line flags.
+    ///
+    /// When parsing, flags that spell the field name exactly are
+    /// accepted as well.
+    pub fn arg_case(mut self, case: ArgCase) -> Config {
+        self.arg_case = case;
+        self
+    }
+
+    /// Sets a format pattern for the struct or tuple struct with the
+    /// given name.
+    ///
+    /// The pattern is text with placeholders in braces that refer to the
+    /// fields of a struct by name or to the fields of a tuple struct by
+    /// index (`{0}`).  Each field is written with the regular scalar rules.
+    /// When parsing, a placeholder extends up to the text that follows it
+    /// in the pattern, so placeholders must be separated by text.  Literal
+    /// braces are written as `{{` and `}}`.
+    ///
+    /// The name is the one serde uses for the type which is the name of
+    /// the struct unless it is renamed.  Malformed patterns are rejected
+    /// right away.
+    ///
+    /// ```rust
+    /// # #[macro_use] extern crate serde_derive;
+    /// use serde_plain::Config;
+    /// # fn main() {
+    ///
+    /// #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    /// pub struct Version {
+    ///     major: u32,
+    ///     minor: u32,
+    ///     patch: u32,
+    /// }
+    ///
+    /// #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    /// pub struct Size(u32, u32);
+    ///
+    /// let config = Config::new()
+    ///     .pattern("Version", "{major}.{minor}.{patch}")
+    ///     .unwrap()
+    ///     .pattern("Size", "{0}x{1}")
+    ///     .unwrap();
+    /// let version = Version { major: 1, minor: 2, patch: 3 };
+    /// assert_eq!(serde_plain::to_string_with_config(&version, &config).unwrap(), "1.2.3");
+    /// assert_eq!(serde_plain::from_str_with_config::<Version>("1.2.3", &config).unwrap(), version);
+    /// assert_eq!(serde_plain::from_str_with_config::<Size>("800x600", &config).unwrap(), Size(800, 600));
+    /// assert!(Config::new().pattern("Size", "{0}{1}").is_err());
+    /// # }
+    /// ```
+    pub fn pattern(mut self, name: &str, pattern: &str) -> Result<Config, Error> {
+        let pattern = Pattern::parse(pattern)?;
+        self.patterns.retain(|(existing, _)| existing != name);
+        self.patterns.push((name.to_string(), pattern));
+        Ok(self)
+    }
+
+    /// Returns the pattern registered for a type.
+    pub(crate) fn pattern_for(&self, name: &str) -> Option<&Pattern> {
+        self.patterns
+            .iter()
+            .find(|(existing, _)| existing == name)
+            .map(|(_, pattern)| pattern)
+    }
+
+    /// Returns the sequence separator in effect, which defaults to a space
+    /// for shell words.
+    pub(crate) fn seq_separator_in_effect(&self) -> Option<&str> {
+        match (self.seq_separator.as_deref(), self.quoting) {
+            (None, Quoting::Shell) => Some(" "),
+            (separator, _) => separator,
+        }
+    }
+
+    /// Returns the sequence separator or the empty string.
+    pub(crate) fn seq_separator_str(&self) -> &str {
+        self.seq_separator_in_effect().unwrap_or("")
+    }
+
+    /// Returns the separators that are in effect.
+    pub(crate) fn separators(&self) -> impl Iterator<Item = &str> {
+        let key_value = self
+            .seq_separator_in_effect()
+            .map(|_| &*self.key_value_separator);
+        self.seq_separator_in_effect()
+            .into_iter()
+            .chain(key_value)
+            .chain(self.tuple_separator.as_deref())
+    }
+
+    /// Fails unless `input` equals its `canonical` spelling in canonical mode.
+    pub(crate) fn check_canonical(
+        &self,
+        ty: &'static str,
+        input: &str,
+        canonical: &str,
+    ) -> Result<(), Error> {
+        if self.parse_mode == ParseMode::Canonical && input != canonical {
+            Err(Error::Parse(
+                ty,
+                format!("non-canonical representation, expected `{}`", canonical),
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Returns the representation of `None` and `()`.
+    pub(crate) fn none_str(&self) -> &str {
+        self.none_repr.as_deref().unwrap_or("")
+    }
+
+    /// Checks if the value is made up of one or more none sentinels.
+    pub(crate) fn is_sentinel_run(&self, value: &str) -> bool {
+        match self.none_repr {
+            Some(ref repr) => {
+                !value.is_empty() && value.trim_start_matches(repr.as_str()).is_empty()
+            }
+            None => false,
+        }
+    }
+}
@@ -1,15 +1,258 @@
 use serde::de::{self, Deserialize, IntoDeserializer, Visitor};
+use std::borrow::Cow;
+use std::fmt;
+use std::ops::Range;
+use std::str::FromStr;
 
+use crate::bytes;
+use crate::config::{Config, ParseMode, DEFAULT};
 use crate::error::Error;
+use crate::float;
+use crate::pattern::Pattern;
+use crate::quote;
 
 /// A simple deserializer that works with plain strings.
+///
+/// This uses the default configuration.  Use
+/// [`Deserializer::with_config`] to deserialize with a custom
+/// configuration.
 pub struct Deserializer<'de> {
-    input: &'de str,
+    inner: ConfiguredDeserializer<'de, 'static>,
 }
 
 impl<'de> Deserializer<'de> {
+    /// Creates a deserializer with the default configuration.
     pub fn new(input: &'de str) -> Self {
-        Deserializer { input }
+        Deserializer {
+            inner: Deserializer::with_config(input, &DEFAULT),
+        }
+    }
+
+    /// Creates a deserializer that uses the given configuration.
+    pub fn with_config<'a>(input: &'de str, config: &'a Config) -> ConfiguredDeserializer<'de, 'a> {
+        ConfiguredDeserializer::nested(Cow::Borrowed(input), 0, config)
+    }
+}
+
+/// A deserializer that uses a configuration.
+///
+/// This is returned by [`Deserializer::with_config`].
+pub struct ConfiguredDeserializer<'de, 'a> {
+    input: Cow<'de, str>,
+    offset: usize,
+    config: &'a Config,
+}
+
+/// Returns the given range of a possibly borrowed string.
+fn slice<'de>(input: &Cow<'de, str>, range: Range<usize>) -> Cow<'de, str> {
+    match *input {
+        Cow::Borrowed(input) => Cow::Borrowed(&input[range]),
+        Cow::Owned(ref input) => Cow::Owned(input[range].to_string()),
+    }
+}
+
+impl<'de, 'a> ConfiguredDeserializer<'de, 'a> {
+    /// Creates a deserializer for a part of the input at the given offset.
+    pub(crate) fn nested(input: Cow<'de, str>, offset: usize, config: &'a Config) -> Self {
+        match config.parse_mode {
+            ParseMode::Trimmed => {
+                let start = input.len() - input.trim_start().len();
+                let end = start + input.trim().len();
+                ConfiguredDeserializer {
+                    input: slice(&input, start..end),
+                    offset: offset + start,
+                    config,
+                }
+            }
+            ParseMode::Exact | ParseMode::Canonical => ConfiguredDeserializer {
+                input,
+                offset,
+                config,
+            },
+        }
+    }
+
+    /// Creates a deserializer for the given range of the input.
+    fn part(&self, range: Range<usize>) -> ConfiguredDeserializer<'de, 'a> {
+        let offset = self.offset + range.start;
+        ConfiguredDeserializer::nested(slice(&self.input, range), offset, self.config)
+    }
+
+    /// Returns the range of the original input this deserializer covers.
+    fn span(&self) -> Range<usize> {
+        self.offset..self.offset + self.input.len()
+    }
+
+    /// Returns the input with quotes removed.
+    fn scalar(&self) -> Result<Cow<'de, str>, Error> {
+        match self.input {
+            Cow::Borrowed(input) => quote::unquote(input, self.config, self.offset),
+            Cow::Owned(ref input) => quote::unquote(input, self.config, self.offset)
+                .map(|value| Cow::Owned(value.into_owned())),
+        }
+    }
+
+    fn parse<T>(&self, ty: &'static str) -> Result<T, Error>
+    where
+        T: FromStr + ToString,
+        T::Err: fmt::Display,
+    {
+        let value: T = self
+            .scalar()?
+            .parse()
+            .map_err(|e| Error::Parse(ty, format!("{}", e)))?;
+        self.config
+            .check_canonical(ty, &self.input, &value.to_string())?;
+        Ok(value)
+    }
+
+    /// Resolves the name of the variant the input refers to.
+    fn variant(&self, variants: &'static [&'static str]) -> Result<Cow<'de, str>, Error> {
+        let input = self.scalar()?;
+        if variants.contains(&&*input) {
+            return Ok(input);
+        }
+        if self.config.accept_variant_index
+            && !input.is_empty()
+            && input.bytes().all(|c| c.is_ascii_digit())
+        {
+            let index: usize = self.parse("variant index")?;
+            return match variants.get(index) {
+                Some(variant) => Ok(Cow::Borrowed(variant)),
+                None => Err(Error::Message(format!(
+                    "variant index {} out of range for {} variants",
+                    index,
+                    variants.len()
+                ))),
+            };
+        }
+
+        if self.config.variant_prefixes && !input.is_empty() {
+            let candidates: Vec<_> = variants
+                .iter()
+                .copied()
+                .filter(|variant| variant.starts_with(&*input))
+                .collect();
+            match candidates.len() {
+                0 => {}
+                1 => {
+                    self.config
+                        .check_canonical("variant", &self.input, candidates[0])?;
+                    return Ok(Cow::Borrowed(candidates[0]));
+                }
+                _ => {
+                    return Err(Error::AmbiguousVariant {
+                        input: input.into_owned(),
+                        candidates,
+                    })
+                }
+            }
+        }
+
+        Ok(input)
+    }
+
+    /// Splits the input into the parts between the sequence separators.
+    fn split(&self, kind: &'static str) -> Result<Vec<ConfiguredDeserializer<'de, 'a>>, Error> {
+        let separator = self
+            .config
+            .seq_separator_in_effect()
+            .ok_or(Error::ImpossibleDeserialization(kind))?;
+        if self.input.is_empty() {
+            return Ok(Vec::new());
+        }
+        Ok(
+            quote::split(&self.input, separator, self.config, self.offset)?
+                .into_iter()
+                .map(|range| self.part(range))
+                .collect(),
+        )
+    }
+
+    /// Splits the input into exactly `len` fields of a tuple.
+    fn split_tuple(
+        &self,
+        kind: &'static str,
+        len: usize,
+    ) -> Result<Vec<ConfiguredDeserializer<'de, 'a>>, Error> {
+        let separator = self
+            .config
+            .tuple_separator
+            .as_deref()
+            .ok_or(Error::ImpossibleDeserialization(kind))?;
+        let fields: Vec<_> = quote::split(&self.input, separator, self.config, self.offset)?
+            .into_iter()
+            .map(|range| self.part(range))
+            .collect();
+        if fields.len() != len {
+            return Err(Error::Message(format!(
+                "expected {} fields, found {}",
+                len,
+                fields.len()
+            )));
+        }
+        Ok(fields)
+    }
+
+    /// Splits the input into `key=value` entries.
+    fn split_entries(
+        &self,
+        kind: &'static str,
+    ) -> Result<
+        Vec<(
+            String,
+            ConfiguredDeserializer<'de, 'a>,
+            ConfiguredDeserializer<'de, 'a>,
+        )>,
+        Error,
+    > {
+        let separator = &*self.config.key_value_separator;
+        self.split(kind)?
+            .into_iter()
+            .map(
+                |entry| match quote::find(&entry.input, separator, entry.config, entry.offset)? {
+                    Some(idx) => {
+                        let key = entry.part(0..idx);
+                        let value = entry.part(idx + separator.len()..entry.input.len());
+                        let segment = match key.scalar() {
+                            Ok(segment) => segment.into_owned(),
+                            Err(_) => key.input.to_string(),
+                        };
+                        Ok((segment, key, value))
+                    }
+                    None => Err(Error::Message(format!(
+                        "expected `{}` in `{}`",
+                        separator, entry.input
+                    ))),
+                },
+            )
+            .collect()
+    }
+
+    /// Splits the input into the placeholders of a pattern.
+    fn split_pattern(
+        &self,
+        pattern: &Pattern,
+    ) -> Result<
+        Vec<(
+            String,
+            ConfiguredDeserializer<'de, 'a>,
+            ConfiguredDeserializer<'de, 'a>,
+        )>,
+        Error,
+    > {
+        Ok(pattern
+            .split(&self.input, self.config, self.offset)?
+            .into_iter()
+            .map(|(name, range)| {
+                let key = ConfiguredDeserializer::nested(
+                    Cow::Owned(name.to_string()),
+                    self.offset + range.start,
+                    self.config,
+                );
+                (name.to_string(), key, self.part(range))
+            })
+            .collect())
     }
 }
 
@@ -25,29 +268,315 @@ where
     T::deserialize(Deserializer::new(s))
 }
 
+/// Deserialize an instance of type `T` from a string of plain text with a
+/// custom configuration.
+///
+/// This works like [`from_str`] but accepts the representation described
+/// by the given [`Config`].
+pub fn from_str_with_config<'a, T>(s: &'a str, config: &Config) -> Result<T, Error>
+where
+    T: Deserialize<'a>,
+{
+    T::deserialize(Deserializer::with_config(s, config))
+}
+
 macro_rules! forward_to_deserialize_from_str {
     ($func:ident, $visit_func:ident, $tymsg:expr) => {
         fn $func<V>(self, visitor: V) -> Result<V::Value, Error>
         where
             V: Visitor<'de>,
         {
-            visitor.$visit_func(
-                self.input
-                    .parse()
-                    .map_err(|e| Error::Parse($tymsg, format!("{}", e)))?,
-            )
+            visitor.$visit_func(self.parse($tymsg)?)
+        }
+    };
+}
+
+/// Feeds the elements of a separated sequence to a visitor.
+///
+/// With `seen` set, elements are rejected if they resolve to an element
+/// that came before them.
+struct SeqAccess<'de, 'a> {
+    items: std::vec::IntoIter<ConfiguredDeserializer<'de, 'a>>,
+    index: usize,
+    seen: Option<Vec<String>>,
+}
+
+impl<'de, 'a> de::SeqAccess<'de> for SeqAccess<'de, 'a> {
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Error>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        let item = match self.items.next() {
+            Some(item) => item,
+            None => return Ok(None),
+        };
+        let span = item.span();
+        let segment = format!("[{}]", self.index);
+        self.index += 1;
+        let seen = match self.seen {
+            Some(ref mut seen) => seen,
+            None => {
+                return seed
+                    .deserialize(item)
+                    .map(Some)
+                    .map_err(|err| err.located(&segment, span))
+            }
+        };
+
+        let input = item.input.to_string();
+        let mut resolved = item.scalar().map(Cow::into_owned).ok();
+        let value = seed
+            .deserialize(UniqueElement {
+                item,
+                resolved: &mut resolved,
+            })
+            .map_err(|err| err.located(&segment, span.clone()))?;
+        let resolved = resolved.unwrap_or_else(|| input.clone());
+        if seen.contains(&resolved) {
+            return Err(
+                Error::Message(format!("duplicate element `{}`", input)).located(&segment, span)
+            );
+        }
+        seen.push(resolved);
+        Ok(Some(value))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.items.len())
+    }
+}
+
+/// Deserializes an element of a sequence with unique elements.
+///
+/// Records the element as the requested type resolves it, so that `1` and
+/// `01` or a variant and its index or prefix count as the same element.
+/// Types that are not resolved keep the unquoted input.
+struct UniqueElement<'de, 'a, 'r> {
+    item: ConfiguredDeserializer<'de, 'a>,
+    resolved: &'r mut Option<String>,
+}
+
+macro_rules! resolve_from_str {
+    ($func:ident, $visit_func:ident, $ty:ty, $tymsg:expr) => {
+        fn $func<V>(self, visitor: V) -> Result<V::Value, Error>
+        where
+            V: Visitor<'de>,
+        {
+            let value: $ty = self.item.parse($tymsg)?;
+            *self.resolved = Some(value.to_string());
+            visitor.$visit_func(value)
+        }
+    };
+}
+
+macro_rules! resolve_float {
+    ($func:ident, $ty:ty, $tymsg:expr) => {
+        fn $func<V>(self, visitor: V) -> Result<V::Value, Error>
+        where
+            V: Visitor<'de>,
+        {
+            let value: $ty = float::parse(&self.item.scalar()?, $tymsg, self.item.config)?;
+            *self.resolved = Some(value.to_string());
+            self.item.$func(visitor)
         }
     };
 }
 
+macro_rules! forward_to_field {
+    ($field:ident; $($func:ident($($arg:ident: $ty:ty),*);)*) => {
+        $(
+            fn $func<V>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value, Error>
+            where
+                V: Visitor<'de>,
+            {
+                self.$field.$func($($arg,)* visitor)
+            }
+        )*
+    };
+}
+
+impl<'de, 'a, 'r> de::Deserializer<'de> for UniqueElement<'de, 'a, 'r> {
+    type Error = Error;
+
+    resolve_from_str!(deserialize_bool, visit_bool, bool, "boolean");
+    resolve_from_str!(deserialize_i8, visit_i8, i8, "i8");
+    resolve_from_str!(deserialize_i16, visit_i16, i16, "i16");
+    resolve_from_str!(deserialize_i32, visit_i32, i32, "i32");
+    resolve_from_str!(deserialize_i64, visit_i64, i64, "i64");
+    resolve_from_str!(deserialize_u8, visit_u8, u8, "u8");
+    resolve_from_str!(deserialize_u16, visit_u16, u16, "u16");
+    resolve_from_str!(deserialize_u32, visit_u32, u32, "u32");
+    resolve_from_str!(deserialize_u64, visit_u64, u64, "u64");
+    resolve_from_str!(deserialize_char, visit_char, char, "char");
+    resolve_float!(deserialize_f32, f32, "f32");
+    resolve_float!(deserialize_f64, f64, "f64");
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        *self.resolved = Some(self.item.variant(variants)?.into_owned());
+        self.item.deserialize_enum(name, variants, visitor)
+    }
+
+    forward_to_field! {
+        item;
+        deserialize_any();
+        deserialize_str();
+        deserialize_string();
+        deserialize_bytes();
+        deserialize_byte_buf();
+        deserialize_option();
+        deserialize_unit();
+        deserialize_unit_struct(name: &'static str);
+        deserialize_seq();
+        deserialize_tuple(len: usize);
+        deserialize_tuple_struct(name: &'static str, len: usize);
+        deserialize_map();
+        deserialize_struct(name: &'static str, fields: &'static [&'static str]);
+        deserialize_identifier();
+        deserialize_ignored_any();
+    }
+}
+
+/// Feeds the entries of a map or struct to a visitor.
+///
+/// Each entry consists of the path segment used for error reporting and
+/// the deserializers for key and value.
+struct MapAccess<'de, 'a> {
+    entries: std::vec::IntoIter<(
+        String,
+        ConfiguredDeserializer<'de, 'a>,
+        ConfiguredDeserializer<'de, 'a>,
+    )>,
+    value: Option<(String, ConfiguredDeserializer<'de, 'a>)>,
+}
+
+impl<'de, 'a> MapAccess<'de, 'a> {
+    fn new(
+        entries: Vec<(
+            String,
+            ConfiguredDeserializer<'de, 'a>,
+            ConfiguredDeserializer<'de, 'a>,
+        )>,
+    ) -> Self {
+        MapAccess {
+            entries: entries.into_iter(),
+            value: None,
+        }
+    }
+}
+
+impl<'de, 'a> de::MapAccess<'de> for MapAccess<'de, 'a> {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Error>
+    where
+        K: de::DeserializeSeed<'de>,
+    {
+        match self.entries.next() {
+            Some((segment, key, value)) => {
+                let span = key.span();
+                let rv = seed
+                    .deserialize(key)
+                    .map(Some)
+                    .map_err(|err| err.located(&segment, span));
+                self.value = Some((segment, value));
+                rv
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Error>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        match self.value.take() {
+            Some((segment, value)) => {
+                let span = value.span();
+                seed.deserialize(value)
+                    .map_err(|err| err.located(&segment, span))
+            }
+            None => Err(Error::Message("value requested before key".into())),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.entries.len())
+    }
+}
+
 impl<'de> de::Deserializer<'de> for Deserializer<'de> {
     type Error = Error;
 
+    forward_to_field! {
+        inner;
+        deserialize_any();
+        deserialize_bool();
+        deserialize_i8();
+        deserialize_i16();
+        deserialize_i32();
+        deserialize_i64();
+        deserialize_u8();
+        deserialize_u16();
+        deserialize_u32();
+        deserialize_u64();
+        deserialize_f32();
+        deserialize_f64();
+        deserialize_char();
+        deserialize_str();
+        deserialize_string();
+        deserialize_bytes();
+        deserialize_byte_buf();
+        deserialize_option();
+        deserialize_unit();
+        deserialize_unit_struct(name: &'static str);
+        deserialize_newtype_struct(name: &'static str);
+        deserialize_seq();
+        deserialize_tuple(len: usize);
+        deserialize_tuple_struct(name: &'static str, len: usize);
+        deserialize_map();
+        deserialize_struct(name: &'static str, fields: &'static [&'static str]);
+        deserialize_enum(name: &'static str, variants: &'static [&'static str]);
+        deserialize_identifier();
+        deserialize_ignored_any();
+    }
+}
+
+impl<'de, 'a> de::Deserializer<'de> for ConfiguredDeserializer<'de, 'a> {
+    type Error = Error;
+
     fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Error>
     where
         V: Visitor<'de>,
     {
-        self.deserialize_str(visitor)
+        // a configured sentinel is reported as unit so that unit structs
+        // and options match inside untagged enums.  The empty string stays
+        // a string.
+        if self.config.none_repr.is_some() && self.input == self.config.none_str() {
+            visitor.visit_unit()
+        } else {
+            self.deserialize_str(visitor)
+        }
     }
 
     forward_to_deserialize_from_str!(deserialize_bool, visit_bool, "boolean");
@@ -59,15 +588,36 @@ impl<'de> de::Deserializer<'de> for Deserializer<'de> {
     forward_to_deserialize_from_str!(deserialize_u16, visit_u16, "u16");
     forward_to_deserialize_from_str!(deserialize_u32, visit_u32, "u32");
     forward_to_deserialize_from_str!(deserialize_u64, visit_u64, "u64");
-    forward_to_deserialize_from_str!(deserialize_f32, visit_f32, "f32");
-    forward_to_deserialize_from_str!(deserialize_f64, visit_f64, "f64");
     forward_to_deserialize_from_str!(deserialize_char, visit_char, "char");
 
+    fn deserialize_f32<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        let value = float::parse(&self.scalar()?, "f32", self.config)?;
+        self.config
+            .check_canonical("f32", &self.input, &float::format(value, self.config)?)?;
+        visitor.visit_f32(value)
+    }
+
+    fn deserialize_f64<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        let value = float::parse(&self.scalar()?, "f64", self.config)?;
+        self.config
+            .check_canonical("f64", &self.input, &float::format(value, self.config)?)?;
+        visitor.visit_f64(value)
+    }
+
     fn deserialize_str<V>(self, visitor: V) -> Result<V::Value, Error>
     where
         V: Visitor<'de>,
     {
-        visitor.visit_borrowed_str(self.input)
+        match self.scalar()? {
+            Cow::Borrowed(value) => visitor.visit_borrowed_str(value),
+            Cow::Owned(value) => visitor.visit_string(value),
+        }
     }
 
     fn deserialize_string<V>(self, visitor: V) -> Result<V::Value, Error>
@@ -77,26 +627,35 @@ impl<'de> de::Deserializer<'de> for Deserializer<'de> {
         self.deserialize_str(visitor)
     }
 
-    fn deserialize_bytes<V>(self, _visitor: V) -> Result<V::Value, Error>
+    fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value, Error>
     where
         V: Visitor<'de>,
     {
-        Err(Error::ImpossibleDeserialization("bytes"))
+        self.deserialize_byte_buf(visitor)
     }
 
-    fn deserialize_byte_buf<V>(self, _visitor: V) -> Result<V::Value, Error>
+    fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value, Error>
     where
         V: Visitor<'de>,
     {
-        Err(Error::ImpossibleDeserialization("bytes"))
+        let encoding = self
+            .config
+            .byte_encoding
+            .ok_or(Error::ImpossibleDeserialization("bytes"))?;
+        let value = bytes::decode(&self.scalar()?, encoding)?;
+        self.config
+            .check_canonical("bytes", &self.input, &bytes::encode(&value, encoding))?;
+        visitor.visit_byte_buf(value)
     }
 
     fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Error>
     where
         V: Visitor<'de>,
     {
-        if self.input.is_empty() {
+        if self.input == self.config.none_str() {
             visitor.visit_none()
+        } else if self.config.is_sentinel_run(&self.input) {
+            visitor.visit_some(self.part(self.config.none_str().len()..self.input.len()))
         } else {
             visitor.visit_some(self)
         }
@@ -106,18 +665,32 @@ impl<'de> de::Deserializer<'de> for Deserializer<'de> {
     where
         V: Visitor<'de>,
     {
-        if self.input.is_empty() {
+        if self.input == self.config.none_str() {
             visitor.visit_unit()
-        } else {
+        } else if self.config.none_repr.is_none() {
             Err(Error::Message("expected empty string for unit".into()))
+        } else {
+            Err(Error::Message(format!(
+                "expected `{}` for unit",
+                self.config.none_str()
+            )))
         }
     }
 
-    fn deserialize_unit_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value, Error>
+    fn deserialize_unit_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value, Error>
     where
         V: Visitor<'de>,
     {
-        self.deserialize_unit(visitor)
+        if !self.config.unit_struct_names {
+            self.deserialize_unit(visitor)
+        } else if self.scalar()? == name {
+            visitor.visit_unit()
+        } else {
+            Err(Error::Message(format!(
+                "expected `{}` for unit struct",
+                name
+            )))
+        }
     }
 
     fn deserialize_newtype_struct<V>(
@@ -131,61 +704,109 @@ impl<'de> de::Deserializer<'de> for Deserializer<'de> {
         visitor.visit_newtype_struct(self)
     }
 
-    fn deserialize_seq<V>(self, _visitor: V) -> Result<V::Value, Error>
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, Error>
     where
         V: Visitor<'de>,
     {
-        Err(Error::ImpossibleDeserialization("seq"))
+        visitor.visit_seq(SeqAccess {
+            items: self.split("seq")?.into_iter(),
+            index: 0,
+            seen: if self.config.seq_unique {
+                Some(Vec::new())
+            } else {
+                None
+            },
+        })
     }
 
-    fn deserialize_tuple<V>(self, _len: usize, _visitor: V) -> Result<V::Value, Error>
+    fn deserialize_tuple<V>(self, len: usize, visitor: V) -> Result<V::Value, Error>
     where
         V: Visitor<'de>,
     {
-        Err(Error::ImpossibleDeserialization("tuple"))
+        visitor.visit_seq(SeqAccess {
+            items: self.split_tuple("tuple", len)?.into_iter(),
+            index: 0,
+            seen: None,
+        })
     }
 
     fn deserialize_tuple_struct<V>(
         self,
-        _name: &'static str,
-        _len: usize,
-        _visitor: V,
+        name: &'static str,
+        len: usize,
+        visitor: V,
     ) -> Result<V::Value, Error>
     where
         V: Visitor<'de>,
     {
-        Err(Error::ImpossibleDeserialization("tuple struct"))
+        let pattern = match self.config.pattern_for(name) {
+            Some(pattern) => pattern,
+            None => {
+                return visitor.visit_seq(SeqAccess {
+                    items: self.split_tuple("tuple struct", len)?.into_iter(),
+                    index: 0,
+                    seen: None,
+                })
+            }
+        };
+        let mut fields = self.split_pattern(pattern)?;
+        let mut items = Vec::with_capacity(len);
+        for idx in 0..len {
+            let pos = fields
+                .iter()
+                .position(|(name, _, _)| *name == idx.to_string())
+                .ok_or_else(|| {
+                    Error::Message(format!("pattern has no placeholder `{{{}}}`", idx))
+                })?;
+            items.push(fields.swap_remove(pos).2);
+        }
+        if let Some((name, _, _)) = fields.first() {
+            return Err(Error::Message(format!(
+                "placeholder `{{{}}}` does not refer to a field",
+                name
+            )));
+        }
+        visitor.visit_seq(SeqAccess {
+            items: items.into_iter(),
+            index: 0,
+            seen: None,
+        })
     }
 
-    fn deserialize_map<V>(self, _visitor: V) -> Result<V::Value, Error>
+    fn deserialize_map<V>(self, visitor: V) -> Result<V::Value, Error>
     where
         V: Visitor<'de>,
     {
-        Err(Error::ImpossibleDeserialization("map"))
+        visitor.visit_map(MapAccess::new(self.split_entries("map")?))
     }
 
     fn deserialize_struct<V>(
         self,
-        _name: &'static str,
+        name: &'static str,
         _fields: &'static [&'static str],
-        _visitor: V,
+        visitor: V,
     ) -> Result<V::Value, Error>
     where
         V: Visitor<'de>,
     {
-        Err(Error::ImpossibleDeserialization("struct"))
+        let entries = match self.config.pattern_for(name) {
+            Some(pattern) => self.split_pattern(pattern)?,
+            None => self.split_entries("struct")?,
+        };
+        visitor.visit_map(MapAccess::new(entries))
     }
 
     fn deserialize_enum<V>(
         self,
         _name: &'static str,
-        _variants: &'static [&'static str],
+        variants: &'static [&'static str],
         visitor: V,
     ) -> Result<V::Value, Error>
     where
         V: Visitor<'de>,
     {
-        visitor.visit_enum(self.input.into_deserializer())
+        let variant = self.variant(variants)?;
+        visitor.visit_enum(variant.into_deserializer())
     }
 
     fn deserialize_identifier<V>(self, visitor: V) -> Result<V::Value, Error>
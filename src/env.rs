@@ -0,0 +1,171 @@
+use std::collections::BTreeMap;
+
+use serde::de::DeserializeOwned;
+use serde::ser;
+
+use crate::config::Config;
+use crate::error::Error;
+use crate::flat::{Entry, FlatDeserializer, FlatSerializer, Position, Scheme};
+
+/// Deserialize a struct from the environment variables of the process.
+///
+/// Every field maps to a variable named after the prefix and the field in
+/// upper case, joined by `_`.  Fields of nested structs are joined with
+/// `__`, so the field `host` of the field `db` is read from `APP_DB__HOST`
+/// for the prefix `APP`.  An empty prefix uses the field names alone.
+///
+/// Each variable is parsed like [`from_str`](crate::from_str).  Variables
+/// that are not set are treated as missing fields, so `Option` and
+/// `#[serde(default)]` fields may be left out.  Errors name the variable
+/// they refer to (`Error::Env`).  Variables that are not valid unicode are
+/// ignored.
+pub fn from_env<T: DeserializeOwned>(prefix: &str) -> Result<T, Error> {
+    from_env_with_config(prefix, &Config::default())
+}
+
+/// Like [`from_env`] but with a custom configuration.
+pub fn from_env_with_config<T: DeserializeOwned>(
+    prefix: &str,
+    config: &Config,
+) -> Result<T, Error> {
+    let vars = std::env::vars_os()
+        .filter_map(|(key, value)| Some((key.into_string().ok()?, value.into_string().ok()?)));
+    from_vars_with_config(prefix, vars, config)
+}
+
+/// Deserialize a struct from the given variables.
+///
+/// This works like [`from_env`] but reads from any source of name and
+/// value pairs, like a map.
+///
+/// ```rust
+/// # #[macro_use] extern crate serde_derive;
+/// use std::collections::HashMap;
+/// # fn main() {
+///
+/// #[derive(Deserialize, Debug, PartialEq)]
+/// pub struct Database {
+///     host: String,
+///     port: u16,
+/// }
+///
+/// #[derive(Deserialize, Debug, PartialEq)]
+/// pub struct Settings {
+///     log_level: String,
+///     workers: Option<u32>,
+///     db: Database,
+/// }
+///
+/// let mut vars = HashMap::new();
+/// vars.insert("APP_LOG_LEVEL", "debug");
+/// vars.insert("APP_DB__HOST", "localhost");
+/// vars.insert("APP_DB__PORT", "5432");
+/// let settings: Settings = serde_plain::from_vars("APP", vars).unwrap();
+/// assert_eq!(settings.workers, None);
+/// assert_eq!(settings.db.port, 5432);
+/// # }
+/// ```
+pub fn from_vars<T, I, K, V>(prefix: &str, vars: I) -> Result<T, Error>
+where
+    T: DeserializeOwned,
+    I: IntoIterator<Item = (K, V)>,
+    K: Into<String>,
+    V: Into<String>,
+{
+    from_vars_with_config(prefix, vars, &Config::default())
+}
+
+/// Like [`from_vars`] but with a custom configuration.
+pub fn from_vars_with_config<T, I, K, V>(prefix: &str, vars: I, config: &Config) -> Result<T, Error>
+where
+    T: DeserializeOwned,
+    I: IntoIterator<Item = (K, V)>,
+    K: Into<String>,
+    V: Into<String>,
+{
+    let vars: BTreeMap<String, Entry> = vars
+        .into_iter()
+        .map(|(key, value)| {
+            let entry = Entry {
+                value: value.into(),
+                position: None,
+            };
+            (key.into(), entry)
+        })
+        .collect();
+    T::deserialize(FlatDeserializer::new(&vars, prefix, config, &ENV))
+}
+
+/// Serialize a struct into environment variable pairs.
+///
+/// This is the inverse of [`from_env`]: every field is written to a
+/// variable named after the prefix and the field, nested structs are
+/// flattened with `__`.  Fields that are `None` are left out.
+///
+/// ```rust
+/// # #[macro_use] extern crate serde_derive;
+/// # fn main() {
+/// #[derive(Serialize)]
+/// pub struct Database {
+///     host: String,
+///     port: u16,
+/// }
+///
+/// #[derive(Serialize)]
+/// pub struct Settings {
+///     workers: Option<u32>,
+///     db: Database,
+/// }
+///
+/// let settings = Settings {
+///     workers: None,
+///     db: Database { host: "localhost".into(), port: 5432 },
+/// };
+/// let pairs = serde_plain::to_env_pairs(&settings, "APP").unwrap();
+/// assert_eq!(pairs, vec![
+///     ("APP_DB__HOST".to_string(), "localhost".to_string()),
+///     ("APP_DB__PORT".to_string(), "5432".to_string()),
+/// ]);
+/// let mut child = std::process::Command::new("env");
+/// child.envs(pairs);
+/// # }
+/// ```
+pub fn to_env_pairs<T: ser::Serialize>(
+    value: &T,
+    prefix: &str,
+) -> Result<Vec<(String, String)>, Error> {
+    to_env_pairs_with_config(value, prefix, &Config::default())
+}
+
+/// Like [`to_env_pairs`] but with a custom configuration.
+pub fn to_env_pairs_with_config<T: ser::Serialize>(
+    value: &T,
+    prefix: &str,
+    config: &Config,
+) -> Result<Vec<(String, String)>, Error> {
+    let mut pairs = Vec::new();
+    value.serialize(FlatSerializer::new(&mut pairs, prefix, config, &ENV))?;
+    Ok(pairs)
+}
+
+/// Variables are named after the prefix and the fields in upper case.
+static ENV: Scheme = Scheme {
+    key: var_name,
+    wrap: |var, _position: Option<Position>, error| Error::Env {
+        var: var.to_string(),
+        error: Box::new(error),
+    },
+    flatten_maps: false,
+};
+
+/// Returns the name of the variable for a field.
+fn var_name(parent: &str, nested: bool, field: &str) -> String {
+    let field = field.to_uppercase().replace('-', "_");
+    if parent.is_empty() {
+        field
+    } else if nested {
+        format!("{}__{}", parent, field)
+    } else {
+        format!("{}_{}", parent, field)
+    }
+}
@@ -1,10 +1,17 @@
 use serde::{de, ser};
 use std::fmt;
+use std::io;
+use std::ops::Range;
+use std::sync::Arc;
 
 use std::error;
 
 /// Errors created from this crate.
+///
+/// New variants may be added in minor releases, so matches on this enum
+/// need a wildcard arm.
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub enum Error {
     /// An impossible / unsupported operation was attempted.
     ImpossibleSerialization(&'static str),
@@ -14,6 +21,224 @@ pub enum Error {
     Parse(&'static str, String),
     /// An arbitrary error message.
     Message(String),
+    /// The input is valid but not in its canonical form.
+    NonCanonical {
+        /// The input as it was given.
+        found: String,
+        /// The canonical representation of the same value.
+        expected: String,
+        /// How the input deviates from the canonical form.
+        kind: NonCanonicalKind,
+    },
+    /// An abbreviated variant name matches more than one variant.
+    AmbiguousVariant {
+        /// The abbreviation as it was given.
+        input: String,
+        /// The variants starting with the abbreviation.
+        candidates: Vec<&'static str>,
+    },
+    /// A quoted string is missing its closing quote.
+    UnterminatedQuote {
+        /// The byte offset of the opening quote.
+        offset: usize,
+    },
+    /// An error within an element of a compound value.
+    Located {
+        /// The byte offset of the failing element in the input.
+        offset: usize,
+        /// The length of the failing element in bytes.
+        len: usize,
+        /// The path to the failing element, like `limits.max` or `[3]`.
+        path: String,
+        /// The error that was raised for the element.
+        error: Box<Error>,
+    },
+    /// An error on a line of line-oriented input or output.
+    Line {
+        /// The line number, starting at 1.
+        line: usize,
+        /// The error that was raised for the line.
+        error: Box<Error>,
+    },
+    /// Reading or writing failed.
+    Io(Arc<io::Error>),
+    /// A required field of a struct is missing.
+    MissingField(&'static str),
+    /// An error in the value of an environment variable.
+    Env {
+        /// The name of the variable.
+        var: String,
+        /// The error that was raised for the variable.
+        error: Box<Error>,
+    },
+    /// An error in a command-line argument.
+    Arg {
+        /// The flag as it would be given on the command line.
+        flag: String,
+        /// The error that was raised for the argument.
+        error: Box<Error>,
+    },
+    /// An error at a key of a text file such as a properties file.
+    Position {
+        /// The key the error refers to, like `db.port`.
+        key: String,
+        /// The line number, starting at 1.  This is `None` if the error is
+        /// not tied to a place in the input, like a missing key or an
+        /// error while serializing.
+        line: Option<usize>,
+        /// The column in characters, starting at 1, if known.
+        column: Option<usize>,
+        /// The error that was raised for the key.
+        error: Box<Error>,
+    },
+}
+
+impl Error {
+    /// Returns the byte range of the input the error refers to, if known.
+    pub fn span(&self) -> Option<Range<usize>> {
+        match *self {
+            Error::UnterminatedQuote { offset } => Some(offset..offset + 1),
+            Error::Located { offset, len, .. } => Some(offset..offset + len),
+            Error::Line { ref error, .. } => error.span(),
+            _ => None,
+        }
+    }
+
+    /// Returns the byte offset of the input the error refers to, if known.
+    pub fn offset(&self) -> Option<usize> {
+        self.span().map(|span| span.start)
+    }
+
+    /// Returns the path to the failing element of a compound value.
+    pub fn path(&self) -> Option<&str> {
+        match *self {
+            Error::Located { ref path, .. } => Some(path),
+            Error::Line { ref error, .. } => error.path(),
+            Error::Position { ref key, .. } => Some(key),
+            _ => None,
+        }
+    }
+
+    /// Returns the line number for errors in line-oriented input or output
+    /// and in text files.
+    pub fn line(&self) -> Option<usize> {
+        match *self {
+            Error::Line { line, .. } => Some(line),
+            Error::Position { line, .. } => line,
+            _ => None,
+        }
+    }
+
+    /// Returns the column for errors in text files, if known.
+    pub fn column(&self) -> Option<usize> {
+        match *self {
+            Error::Position { column, .. } => column,
+            _ => None,
+        }
+    }
+
+    /// Renders the input with a caret under the span the error refers to.
+    ///
+    /// This returns `None` if the error does not carry a position.
+    ///
+    /// ```rust
+    /// use serde_plain::Config;
+    ///
+    /// let config = Config::new().seq_separator(",");
+    /// let input = "1,2,x,4";
+    /// let err = serde_plain::from_str_with_config::<Vec<u32>>(input, &config).unwrap_err();
+    /// assert_eq!(err.path(), Some("[2]"));
+    /// assert_eq!(err.render(input).unwrap(), "1,2,x,4\n    ^");
+    /// ```
+    pub fn render(&self, input: &str) -> Option<String> {
+        let span = self.span()?;
+        let start = input.get(..span.start)?.chars().count();
+        let len = input.get(span).map_or(1, |s| s.chars().count().max(1));
+        Some(format!(
+            "{}\n{}{}",
+            input,
+            " ".repeat(start),
+            "^".repeat(len)
+        ))
+    }
+
+    /// Moves the location of the error by `by` bytes.
+    ///
+    /// This is used for errors of a value that was parsed on its own and
+    /// which starts at `by` in the original input.
+    pub(crate) fn shifted(self, by: usize) -> Error {
+        match self {
+            Error::Located {
+                offset,
+                len,
+                path,
+                error,
+            } => Error::Located {
+                offset: offset + by,
+                len,
+                path,
+                error,
+            },
+            Error::UnterminatedQuote { offset } => Error::UnterminatedQuote {
+                offset: offset + by,
+            },
+            error => error,
+        }
+    }
+
+    /// Attaches the location of an element to the error.
+    ///
+    /// Errors that are already located get the segment prepended to their
+    /// path, as the innermost location is the most precise one.
+    pub(crate) fn located(self, segment: &str, span: Range<usize>) -> Error {
+        match self {
+            Error::Located {
+                offset,
+                len,
+                path,
+                error,
+            } => Error::Located {
+                offset,
+                len,
+                path: if path.starts_with('[') {
+                    format!("{}{}", segment, path)
+                } else {
+                    format!("{}.{}", segment, path)
+                },
+                error,
+            },
+            error => Error::Located {
+                offset: span.start,
+                len: span.len(),
+                path: segment.to_string(),
+                error: Box::new(error),
+            },
+        }
+    }
+}
+
+/// Describes how a value deviates from its canonical representation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NonCanonicalKind {
+    /// The input only differs in case.
+    Case,
+    /// The input has surrounding whitespace.
+    Whitespace,
+    /// The input is a differently spelled number (`+1`, `01`, `1.0`).
+    Number,
+    /// The input is an alternative spelling such as a `#[serde(alias)]`.
+    Alias,
+}
+
+impl fmt::Display for NonCanonicalKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            NonCanonicalKind::Case => "different case",
+            NonCanonicalKind::Whitespace => "surrounding whitespace",
+            NonCanonicalKind::Number => "non-canonical number",
+            NonCanonicalKind::Alias => "alternative spelling",
+        })
+    }
 }
 
 impl ser::Error for Error {
@@ -26,8 +251,20 @@ impl de::Error for Error {
     fn custom<T: fmt::Display>(msg: T) -> Error {
         Error::Message(msg.to_string())
     }
+
+    fn missing_field(field: &'static str) -> Error {
+        Error::MissingField(field)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Error {
+        Error::Io(Arc::new(error))
+    }
 }
 
+// the wrapped errors are part of the message, so they are not reported as
+// sources which would print them twice in error chains.
 impl error::Error for Error {}
 
 impl fmt::Display for Error {
@@ -41,6 +278,64 @@ impl fmt::Display for Error {
             }
             Error::Parse(ref ty, ref msg) => write!(f, "cannot parse {}: {}", ty, msg),
             Error::Message(ref msg) => write!(f, "{}", msg.as_str()),
+            Error::NonCanonical {
+                ref found,
+                ref expected,
+                kind,
+            } => write!(
+                f,
+                "non-canonical value `{}` ({}), expected `{}`",
+                found, kind, expected
+            ),
+            Error::AmbiguousVariant {
+                ref input,
+                ref candidates,
+            } => {
+                write!(f, "ambiguous variant `{}`, could be ", input)?;
+                for (idx, candidate) in candidates.iter().enumerate() {
+                    if idx > 0 {
+                        f.write_str(if idx + 1 == candidates.len() {
+                            " or "
+                        } else {
+                            ", "
+                        })?;
+                    }
+                    write!(f, "`{}`", candidate)?;
+                }
+                Ok(())
+            }
+            Error::UnterminatedQuote { offset } => {
+                write!(f, "unterminated quote at offset {}", offset)
+            }
+            Error::Located {
+                offset,
+                ref path,
+                ref error,
+                ..
+            } => write!(f, "{} at `{}` (offset {})", error, path, offset),
+            Error::Line { line, ref error } => write!(f, "{} on line {}", error, line),
+            Error::Io(ref error) => write!(f, "io error: {}", error),
+            Error::MissingField(field) => write!(f, "missing field `{}`", field),
+            Error::Env { ref var, ref error } => {
+                write!(f, "{} in environment variable `{}`", error, var)
+            }
+            Error::Arg {
+                ref flag,
+                ref error,
+            } => write!(f, "{} for argument `{}`", error, flag),
+            Error::Position {
+                ref key,
+                line: Some(line),
+                column: Some(column),
+                ref error,
+            } => write!(
+                f,
+                "{} for key `{}` at line {}, column {}",
+                error, key, line, column
+            ),
+            Error::Position {
+                ref key, ref error, ..
+            } => write!(f, "{} for key `{}`", error, key),
         }
     }
 }
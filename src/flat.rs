@@ -0,0 +1,616 @@
+use std::collections::BTreeMap;
+
+use serde::de::{self, Visitor};
+use serde::ser;
+
+use crate::config::Config;
+use crate::de::{ConfiguredDeserializer, Deserializer};
+use crate::error::Error;
+use crate::leaf::{Leaf, Sink};
+use crate::ser::{ConfiguredSerializer, MapSerializer, SeqSerializer, Serializer, TupleSerializer};
+
+/// Describes how a format names the keys of flattened structs.
+pub(crate) struct Scheme {
+    /// Returns the key of a field.  `nested` is `true` if the parent is a
+    /// field itself rather than the prefix.
+    pub(crate) key: fn(parent: &str, nested: bool, field: &str) -> String,
+    /// Attaches the key and position of a value to an error.
+    pub(crate) wrap: fn(key: &str, position: Option<Position>, error: Error) -> Error,
+    /// Whether maps are flattened like structs instead of being written
+    /// as a single value.
+    pub(crate) flatten_maps: bool,
+}
+
+/// Where a value is located in the source.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Position {
+    /// A line and column of a text file, both starting at 1.
+    Text { line: usize, column: usize },
+    /// A byte range of single line input.
+    Span { offset: usize, len: usize },
+}
+
+/// Attaches the key and position of a value to an error.
+pub(crate) fn wrap_position(key: &str, position: Option<Position>, error: Error) -> Error {
+    match position {
+        Some(Position::Text { line, column }) => Error::Position {
+            key: key.to_string(),
+            line: Some(line),
+            column: Some(column),
+            error: Box::new(error),
+        },
+        Some(Position::Span { offset, len }) => {
+            // errors within the value are relative to the value.
+            error.shifted(offset).located(key, offset..offset + len)
+        }
+        None => Error::Position {
+            key: key.to_string(),
+            line: None,
+            column: None,
+            error: Box::new(error),
+        },
+    }
+}
+
+/// Joins keys with dots, like `db.port`.
+pub(crate) fn dotted_key(parent: &str, _nested: bool, field: &str) -> String {
+    if parent.is_empty() {
+        field.to_string()
+    } else {
+        format!("{}.{}", parent, field)
+    }
+}
+
+/// A value of a flattened struct.
+pub(crate) struct Entry {
+    pub(crate) value: String,
+    pub(crate) position: Option<Position>,
+}
+
+/// Deserializes the entry with the given key or, for structs and maps, the
+/// entries nested below the key.
+pub(crate) struct FlatDeserializer<'de, 'a> {
+    entries: &'de BTreeMap<String, Entry>,
+    name: String,
+    nested: bool,
+    config: &'a Config,
+    scheme: &'a Scheme,
+}
+
+impl<'de, 'a> FlatDeserializer<'de, 'a> {
+    pub(crate) fn new(
+        entries: &'de BTreeMap<String, Entry>,
+        prefix: &str,
+        config: &'a Config,
+        scheme: &'a Scheme,
+    ) -> FlatDeserializer<'de, 'a> {
+        FlatDeserializer {
+            entries,
+            name: prefix.to_string(),
+            nested: false,
+            config,
+            scheme,
+        }
+    }
+
+    fn child(&self, field: &str) -> FlatDeserializer<'de, 'a> {
+        FlatDeserializer {
+            entries: self.entries,
+            name: (self.scheme.key)(&self.name, self.nested, field),
+            nested: true,
+            config: self.config,
+            scheme: self.scheme,
+        }
+    }
+
+    /// Returns the common prefix of all nested keys.
+    fn prefix(&self) -> String {
+        (self.scheme.key)(&self.name, self.nested, "")
+    }
+
+    /// Iterates over the entries nested below the key.
+    fn nested_entries(&self) -> impl Iterator<Item = (&'de str, &'de Entry)> {
+        let prefix = self.prefix();
+        self.entries
+            .range(prefix.clone()..)
+            .take_while(move |(key, _)| key.starts_with(&prefix))
+            .map(|(key, entry)| (key.as_str(), entry))
+    }
+
+    /// Returns `true` if the entry or any nested entry is set.
+    fn is_present(&self) -> bool {
+        self.entries.contains_key(&self.name) || self.nested_entries().next().is_some()
+    }
+
+    /// Parses the value of the entry with `f`.
+    fn value<T, F>(self, f: F) -> Result<T, Error>
+    where
+        F: FnOnce(ConfiguredDeserializer<'de, 'a>) -> Result<T, Error>,
+    {
+        match self.entries.get(&self.name) {
+            Some(entry) => f(Deserializer::with_config(&entry.value, self.config))
+                .map_err(|error| (self.scheme.wrap)(&self.name, entry.position, error)),
+            None => Err((self.scheme.wrap)(
+                &self.name,
+                None,
+                Error::Message("value is not set".into()),
+            )),
+        }
+    }
+}
+
+macro_rules! forward_to_value {
+    ($($func:ident)*) => {
+        $(
+            fn $func<V>(self, visitor: V) -> Result<V::Value, Error>
+            where
+                V: Visitor<'de>,
+            {
+                self.value(|de| de::Deserializer::$func(de, visitor))
+            }
+        )*
+    };
+}
+
+impl<'de, 'a> de::Deserializer<'de> for FlatDeserializer<'de, 'a> {
+    type Error = Error;
+
+    forward_to_value!(deserialize_any deserialize_bool deserialize_i8 deserialize_i16
+        deserialize_i32 deserialize_i64 deserialize_i128 deserialize_u8 deserialize_u16
+        deserialize_u32 deserialize_u64 deserialize_u128 deserialize_f32 deserialize_f64
+        deserialize_char deserialize_str deserialize_string deserialize_bytes
+        deserialize_byte_buf deserialize_unit deserialize_seq deserialize_identifier
+        deserialize_ignored_any);
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        if self.is_present() {
+            visitor.visit_some(self)
+        } else {
+            visitor.visit_none()
+        }
+    }
+
+    fn deserialize_unit_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.value(|de| de::Deserializer::deserialize_unit_struct(de, name, visitor))
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_tuple<V>(self, len: usize, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.value(|de| de::Deserializer::deserialize_tuple(de, len, visitor))
+    }
+
+    fn deserialize_tuple_struct<V>(
+        self,
+        name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.value(|de| de::Deserializer::deserialize_tuple_struct(de, name, len, visitor))
+    }
+
+    fn deserialize_map<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        // a map that is set as a single value uses the regular
+        // representation, otherwise the nested keys are the map keys.  The
+        // root is never a single value, so an empty key is a regular key.
+        if self.nested && self.entries.contains_key(&self.name) {
+            return self.value(|de| de::Deserializer::deserialize_map(de, visitor));
+        }
+        let prefix_len = self.prefix().len();
+        let entries: Vec<_> = self
+            .nested_entries()
+            .map(|(key, _)| {
+                let value = FlatDeserializer {
+                    entries: self.entries,
+                    name: key.to_string(),
+                    nested: true,
+                    config: self.config,
+                    scheme: self.scheme,
+                };
+                (&key[prefix_len..], value)
+            })
+            .collect();
+        visitor.visit_map(FlatMapAccess {
+            entries: entries.into_iter(),
+            value: None,
+            config: self.config,
+        })
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        // a struct that is set as a single value uses the regular
+        // representation, such as a pattern.  This does not apply to the
+        // root, see `deserialize_map`.
+        if self.nested && self.entries.contains_key(&self.name) {
+            return self
+                .value(|de| de::Deserializer::deserialize_struct(de, name, fields, visitor));
+        }
+        let entries: Vec<_> = fields
+            .iter()
+            .map(|field| (*field, self.child(field)))
+            .filter(|(_, child)| child.is_present())
+            .collect();
+        let rv = visitor.visit_map(FlatMapAccess {
+            entries: entries.into_iter(),
+            value: None,
+            config: self.config,
+        });
+        rv.map_err(|error| match error {
+            Error::MissingField(field) => {
+                (self.scheme.wrap)(&self.child(field).name, None, Error::MissingField(field))
+            }
+            error => error,
+        })
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.value(|de| de::Deserializer::deserialize_enum(de, name, variants, visitor))
+    }
+}
+
+/// Feeds the fields of a struct or the entries of a map to a visitor.
+struct FlatMapAccess<'de, 'a> {
+    entries: std::vec::IntoIter<(&'de str, FlatDeserializer<'de, 'a>)>,
+    value: Option<FlatDeserializer<'de, 'a>>,
+    config: &'a Config,
+}
+
+impl<'de, 'a> de::MapAccess<'de> for FlatMapAccess<'de, 'a> {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Error>
+    where
+        K: de::DeserializeSeed<'de>,
+    {
+        match self.entries.next() {
+            Some((key, value)) => {
+                self.value = Some(value);
+                seed.deserialize(Deserializer::with_config(key, self.config))
+                    .map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Error>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        match self.value.take() {
+            Some(value) => seed.deserialize(value),
+            None => Err(Error::Message("value requested before key".into())),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.entries.len())
+    }
+}
+
+/// Serializes a value into the entry with the given key or, for structs,
+/// into the entries of its fields.
+pub(crate) struct FlatSerializer<'a> {
+    pairs: &'a mut Vec<(String, String)>,
+    name: String,
+    nested: bool,
+    config: &'a Config,
+    scheme: &'a Scheme,
+}
+
+impl<'a> FlatSerializer<'a> {
+    pub(crate) fn new(
+        pairs: &'a mut Vec<(String, String)>,
+        prefix: &str,
+        config: &'a Config,
+        scheme: &'a Scheme,
+    ) -> FlatSerializer<'a> {
+        FlatSerializer {
+            pairs,
+            name: prefix.to_string(),
+            nested: false,
+            config,
+            scheme,
+        }
+    }
+
+    fn child(&mut self, field: &str) -> FlatSerializer<'_> {
+        FlatSerializer {
+            pairs: &mut *self.pairs,
+            name: (self.scheme.key)(&self.name, self.nested, field),
+            nested: true,
+            config: self.config,
+            scheme: self.scheme,
+        }
+    }
+
+    fn plain(&self) -> ConfiguredSerializer<'a> {
+        Serializer::with_config(self.config)
+    }
+}
+
+impl<'a> Sink for FlatSerializer<'a> {
+    fn push(self, value: Result<String, Error>) -> Result<(), Error> {
+        if self.name.is_empty() {
+            return Err(Error::Message(
+                "cannot serialize a value without a prefix".into(),
+            ));
+        }
+        match value {
+            Ok(value) => {
+                self.pairs.push((self.name, value));
+                Ok(())
+            }
+            Err(error) => Err(self.wrap(error)),
+        }
+    }
+
+    fn wrap(&self, error: Error) -> Error {
+        (self.scheme.wrap)(&self.name, None, error)
+    }
+}
+
+macro_rules! serialize_as_entry {
+    ($($ty:ty => $meth:ident,)*) => {
+        $(fn $meth(self, v: $ty) -> Result<(), Error> {
+            let value = ser::Serializer::$meth(self.plain(), v);
+            self.push(value)
+        })*
+    };
+}
+
+impl<'a> ser::Serializer for FlatSerializer<'a> {
+    type Ok = ();
+    type Error = Error;
+    type SerializeSeq = Leaf<FlatSerializer<'a>, SeqSerializer<'a>>;
+    type SerializeTuple = Leaf<FlatSerializer<'a>, TupleSerializer<'a>>;
+    type SerializeTupleStruct = Leaf<FlatSerializer<'a>, TupleSerializer<'a>>;
+    type SerializeTupleVariant = ser::Impossible<(), Error>;
+    type SerializeMap = FlatMapSerializer<'a>;
+    type SerializeStruct = FlatStructSerializer<'a>;
+    type SerializeStructVariant = ser::Impossible<(), Error>;
+
+    serialize_as_entry! {
+        bool => serialize_bool,
+        u8  => serialize_u8,
+        u16 => serialize_u16,
+        u32 => serialize_u32,
+        u64 => serialize_u64,
+        u128 => serialize_u128,
+        i8  => serialize_i8,
+        i16 => serialize_i16,
+        i32 => serialize_i32,
+        i64 => serialize_i64,
+        i128 => serialize_i128,
+        f32 => serialize_f32,
+        f64 => serialize_f64,
+        char => serialize_char,
+        &str => serialize_str,
+        &[u8] => serialize_bytes,
+    }
+
+    fn serialize_unit(self) -> Result<(), Error> {
+        let value = ser::Serializer::serialize_unit(self.plain());
+        self.push(value)
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<(), Error> {
+        let value = ser::Serializer::serialize_unit_struct(self.plain(), name);
+        self.push(value)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+    ) -> Result<(), Error> {
+        let value =
+            ser::Serializer::serialize_unit_variant(self.plain(), name, variant_index, variant);
+        self.push(value)
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + ser::Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + ser::Serialize>(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        let value = ser::Serializer::serialize_newtype_variant(
+            self.plain(),
+            name,
+            variant_index,
+            variant,
+            value,
+        );
+        self.push(value)
+    }
+
+    fn serialize_none(self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn serialize_some<T: ?Sized + ser::Serialize>(self, value: &T) -> Result<(), Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Error> {
+        let inner = ser::Serializer::serialize_seq(self.plain(), len);
+        Leaf::new(self, inner)
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Error> {
+        let inner = ser::Serializer::serialize_tuple(self.plain(), len);
+        Leaf::new(self, inner)
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Error> {
+        let inner = ser::Serializer::serialize_tuple_struct(self.plain(), name, len);
+        Leaf::new(self, inner)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Error> {
+        Err(self.wrap(Error::ImpossibleSerialization("tuple variant")))
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, Error> {
+        if self.scheme.flatten_maps {
+            return Ok(FlatMapSerializer::Entries(self, None));
+        }
+        let inner = ser::Serializer::serialize_map(self.plain(), len);
+        Leaf::new(self, inner).map(|leaf| FlatMapSerializer::Leaf(Box::new(leaf)))
+    }
+
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, Error> {
+        // structs with a pattern are written as a single entry which is
+        // what the deserializer expects.
+        if self.config.pattern_for(name).is_some() {
+            let inner = ser::Serializer::serialize_struct(self.plain(), name, len);
+            return Leaf::new(self, inner).map(|leaf| FlatStructSerializer::Leaf(Box::new(leaf)));
+        }
+        Ok(FlatStructSerializer::Fields(self))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Error> {
+        Err(self.wrap(Error::ImpossibleSerialization("struct variant")))
+    }
+}
+
+/// Serializes the entries of a map into entries of their own, or the
+/// entire map into a single entry.
+pub(crate) enum FlatMapSerializer<'a> {
+    Entries(FlatSerializer<'a>, Option<String>),
+    Leaf(Box<Leaf<FlatSerializer<'a>, MapSerializer<'a>>>),
+}
+
+impl<'a> ser::SerializeMap for FlatMapSerializer<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<T: ?Sized + ser::Serialize>(&mut self, key: &T) -> Result<(), Error> {
+        match *self {
+            FlatMapSerializer::Entries(ref flat, ref mut pending) => {
+                *pending = Some(key.serialize(flat.plain())?);
+                Ok(())
+            }
+            FlatMapSerializer::Leaf(ref mut leaf) => leaf.serialize_key(key),
+        }
+    }
+
+    fn serialize_value<T: ?Sized + ser::Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        match *self {
+            FlatMapSerializer::Entries(ref mut flat, ref mut pending) => {
+                let key = pending
+                    .take()
+                    .ok_or_else(|| Error::Message("value serialized before key".into()))?;
+                value.serialize(flat.child(&key))
+            }
+            FlatMapSerializer::Leaf(ref mut leaf) => leaf.serialize_value(value),
+        }
+    }
+
+    fn end(self) -> Result<(), Error> {
+        match self {
+            FlatMapSerializer::Entries(..) => Ok(()),
+            FlatMapSerializer::Leaf(leaf) => leaf.end(),
+        }
+    }
+}
+
+/// Serializes the fields of a struct into entries of their own, or the
+/// entire struct into one entry if it has a pattern.
+pub(crate) enum FlatStructSerializer<'a> {
+    Fields(FlatSerializer<'a>),
+    Leaf(Box<Leaf<FlatSerializer<'a>, MapSerializer<'a>>>),
+}
+
+impl<'a> ser::SerializeStruct for FlatStructSerializer<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + ser::Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        match *self {
+            FlatStructSerializer::Fields(ref mut flat) => value.serialize(flat.child(key)),
+            FlatStructSerializer::Leaf(ref mut leaf) => leaf.serialize_field(key, value),
+        }
+    }
+
+    fn end(self) -> Result<(), Error> {
+        match self {
+            FlatStructSerializer::Fields(_) => Ok(()),
+            FlatStructSerializer::Leaf(leaf) => leaf.end(),
+        }
+    }
+}
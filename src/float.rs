@@ -0,0 +1,97 @@
+use std::fmt::{Display, LowerExp};
+use std::num::ParseFloatError;
+use std::str::FromStr;
+
+use crate::config::{Config, FloatFormat};
+use crate::error::Error;
+
+/// Abstracts over `f32` and `f64` for formatting and parsing.
+pub(crate) trait Float: Copy + Display + LowerExp + FromStr<Err = ParseFloatError> {
+    const NAN: Self;
+    const INFINITY: Self;
+    const NEG_INFINITY: Self;
+
+    fn is_nan(self) -> bool;
+    fn is_finite(self) -> bool;
+    fn is_sign_negative(self) -> bool;
+}
+
+macro_rules! impl_float {
+    ($($ty:ident),*) => {
+        $(impl Float for $ty {
+            const NAN: Self = $ty::NAN;
+            const INFINITY: Self = $ty::INFINITY;
+            const NEG_INFINITY: Self = $ty::NEG_INFINITY;
+
+            fn is_nan(self) -> bool {
+                $ty::is_nan(self)
+            }
+
+            fn is_finite(self) -> bool {
+                $ty::is_finite(self)
+            }
+
+            fn is_sign_negative(self) -> bool {
+                $ty::is_sign_negative(self)
+            }
+        })*
+    };
+}
+
+impl_float!(f32, f64);
+
+/// Formats a float according to the configuration.
+pub(crate) fn format<F: Float>(value: F, config: &Config) -> Result<String, Error> {
+    if !value.is_finite() {
+        if !config.allow_non_finite {
+            return Err(Error::Message(
+                "non-finite float values are not allowed".into(),
+            ));
+        }
+        let repr = if value.is_nan() {
+            &config.nan_repr
+        } else if value.is_sign_negative() {
+            &config.neg_infinity_repr
+        } else {
+            &config.infinity_repr
+        };
+        return Ok(match *repr {
+            Some(ref repr) => repr.clone(),
+            None => value.to_string(),
+        });
+    }
+
+    Ok(match config.float_format {
+        FloatFormat::Shortest => value.to_string(),
+        FloatFormat::Decimal => {
+            let mut rv = value.to_string();
+            if !rv.contains('.') {
+                rv.push_str(".0");
+            }
+            rv
+        }
+        FloatFormat::Fixed(precision) => format!("{:.*}", precision, value),
+        FloatFormat::Scientific => format!("{:e}", value),
+    })
+}
+
+/// Parses a float according to the configuration.
+pub(crate) fn parse<F: Float>(input: &str, ty: &'static str, config: &Config) -> Result<F, Error> {
+    let value = if config.nan_repr.as_deref() == Some(input) {
+        F::NAN
+    } else if config.infinity_repr.as_deref() == Some(input) {
+        F::INFINITY
+    } else if config.neg_infinity_repr.as_deref() == Some(input) {
+        F::NEG_INFINITY
+    } else {
+        input
+            .parse()
+            .map_err(|e| Error::Parse(ty, format!("{}", e)))?
+    };
+
+    if !value.is_finite() && !config.allow_non_finite {
+        return Err(Error::Parse(ty, "non-finite values are not allowed".into()));
+    }
+
+    Ok(value)
+}
@@ -0,0 +1,1237 @@
+//! Reading and writing HTTP header values.
+//!
+//! Header values such as `Accept` or `Cache-Control` use the list and
+//! parameter grammar of RFC 9110: elements are separated by commas,
+//! parameters by semicolons and values may be quoted-strings.  This module
+//! maps that grammar onto Rust types:
+//!
+//! * a sequence is the comma-separated list of elements,
+//! * a tuple of a value and a struct or map is a single element with its
+//!   parameters, like `text/html;q=0.9`,
+//! * a struct or map at the top level are the `name=value` directives of
+//!   the header, like `max-age=60, private`, and within a list the
+//!   parameters of an element.  Names are matched in lower case, a name
+//!   without a value is read as `true` and left out names read as
+//!   `false`, as an empty sequence or as `None`.  Within a list, a field
+//!   that is given through its name in one element and through an alias
+//!   in another cannot be left out in a third.  Nested structs are
+//!   written as a single value, which requires a pattern or a sequence
+//!   separator,
+//! * everything else is a single value.
+//!
+//! ```rust
+//! # #[macro_use] extern crate serde_derive;
+//! # fn main() {
+//! #[derive(Deserialize, Serialize, Debug, PartialEq)]
+//! pub struct Params {
+//!     q: Option<f32>,
+//! }
+//!
+//! #[derive(Deserialize, Serialize, Debug, PartialEq)]
+//! pub struct CacheControl {
+//!     #[serde(rename = "max-age")]
+//!     max_age: Option<u32>,
+//!     private: bool,
+//! }
+//!
+//! let accept: Vec<(String, Params)> =
+//!     serde_plain::http_header::from_str("text/html;q=0.9, application/json").unwrap();
+//! assert_eq!(accept[0], ("text/html".to_string(), Params { q: Some(0.9) }));
+//! assert_eq!(accept[1], ("application/json".to_string(), Params { q: None }));
+//!
+//! let cache_control: CacheControl =
+//!     serde_plain::http_header::from_str("Max-Age=60, private").unwrap();
+//! assert_eq!(cache_control.max_age, Some(60));
+//! assert_eq!(
+//!     serde_plain::http_header::to_string(&cache_control).unwrap(),
+//!     "max-age=60, private"
+//! );
+//! # }
+//! ```
+//!
+//! The individual values are parsed and formatted like
+//! [`from_str`](crate::from_str) and [`to_string`](crate::to_string) do.
+//! Errors carry the path and byte range of the failing value
+//! (`Error::Located`).
+use std::ops::Range;
+
+use serde::de::{self, DeserializeOwned, Visitor};
+use serde::ser;
+
+use crate::config::{Config, Quoting};
+use crate::de::{ConfiguredDeserializer, Deserializer};
+use crate::error::Error;
+use crate::flat::wrap_position;
+use crate::leaf::{Leaf, Sink};
+use crate::missing::{resolve_left_out, Entry, LeftOutFields, MissingDeserializer, Skipped};
+use crate::quote;
+use crate::ser::{ConfiguredSerializer, MapSerializer, SeqSerializer, Serializer, TupleSerializer};
+
+/// Deserialize a value from an HTTP header value.
+pub fn from_str<T: DeserializeOwned>(s: &str) -> Result<T, Error> {
+    from_str_with_config(s, &Config::default())
+}
+
+/// Like [`from_str`] but with a custom configuration.
+pub fn from_str_with_config<T: DeserializeOwned>(s: &str, config: &Config) -> Result<T, Error> {
+    let header = parse(s)?;
+    resolve_left_out(|skipped| {
+        T::deserialize(HeaderDeserializer {
+            header: &header,
+            config,
+            skipped,
+        })
+    })
+}
+
+/// Serialize a value into an HTTP header value.
+///
+/// List elements are separated by `, `, parameters by `;`.  Values that
+/// are not valid tokens are written as quoted-strings, `bool` parameters
+/// are written as their name alone if `true` and left out if `false`, and
+/// parameters that are `None` are left out.
+pub fn to_string<T: ser::Serialize>(value: &T) -> Result<String, Error> {
+    to_string_with_config(value, &Config::default())
+}
+
+/// Like [`to_string`] but with a custom configuration.
+pub fn to_string_with_config<T: ser::Serialize>(
+    value: &T,
+    config: &Config,
+) -> Result<String, Error> {
+    value.serialize(HeaderSerializer { config })
+}
+
+/// A value of the header with its location in the input.
+struct Value {
+    text: String,
+    span: Range<usize>,
+}
+
+/// A `name=value` parameter or a bare token.
+struct Param {
+    /// The name in lower case.
+    name: String,
+    /// The entire parameter, for the leading value of an element.
+    head: Value,
+    /// The value, which is `true` for bare tokens.
+    value: Value,
+}
+
+/// An element of the comma-separated list.
+struct Element {
+    raw: Value,
+    params: Vec<Param>,
+}
+
+struct Header {
+    raw: Value,
+    elements: Vec<Element>,
+}
+
+/// Returns true for the characters allowed in tokens by RFC 9110.
+fn is_tchar(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(c)
+}
+
+/// Trims optional whitespace from a range of the input.
+fn trim(s: &str, range: Range<usize>) -> Range<usize> {
+    let part = &s[range.clone()];
+    let start = range.start + (part.len() - part.trim_start().len());
+    start..start + part.trim().len()
+}
+
+/// Returns the index of the quote that closes the quoted-string at the
+/// start of `s`.
+fn closing_quote(s: &str) -> Option<usize> {
+    let bytes = s.as_bytes();
+    let mut idx = 1;
+    while idx < bytes.len() {
+        match bytes[idx] {
+            b'\\' => idx += 2,
+            b'"' => return Some(idx),
+            _ => idx += 1,
+        }
+    }
+    None
+}
+
+/// Resolves a quoted-string, other values are returned unchanged.
+fn unquote(s: &str, range: Range<usize>) -> Value {
+    let part = &s[range.clone()];
+    let is_quoted = part.starts_with('"') && closing_quote(part) == Some(part.len() - 1);
+    if !is_quoted {
+        return Value {
+            text: part.to_string(),
+            span: range,
+        };
+    }
+    let mut text = String::new();
+    let mut chars = part[1..part.len() - 1].chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => text.extend(chars.next()),
+            c => text.push(c),
+        }
+    }
+    Value {
+        text,
+        span: range.start + 1..range.end - 1,
+    }
+}
+
+/// Splits a range of the input at separators outside of quoted-strings.
+fn split(
+    s: &str,
+    range: Range<usize>,
+    separator: &str,
+    quoted: &Config,
+) -> Result<Vec<Range<usize>>, Error> {
+    Ok(
+        quote::split(&s[range.clone()], separator, quoted, range.start)?
+            .into_iter()
+            .map(|part| trim(s, range.start + part.start..range.start + part.end))
+            .filter(|part| !part.is_empty())
+            .collect(),
+    )
+}
+
+fn parse(s: &str) -> Result<Header, Error> {
+    // only used to find separators outside of quoted-strings.
+    let quoted = Config::new().quoting(Quoting::DoubleQuotes);
+    let mut elements = Vec::new();
+    for element in split(s, 0..s.len(), ",", &quoted)? {
+        let mut params = Vec::new();
+        for part in split(s, element.clone(), ";", &quoted)? {
+            let param = match quote::find(&s[part.clone()], "=", &quoted, part.start)? {
+                Some(idx) => {
+                    let name = trim(s, part.start..part.start + idx);
+                    Param {
+                        name: s[name].to_ascii_lowercase(),
+                        head: unquote(s, part.clone()),
+                        value: unquote(s, trim(s, part.start + idx + 1..part.end)),
+                    }
+                }
+                None => Param {
+                    name: s[part.clone()].to_ascii_lowercase(),
+                    head: unquote(s, part.clone()),
+                    value: Value {
+                        text: "true".into(),
+                        span: part,
+                    },
+                },
+            };
+            params.push(param);
+        }
+        elements.push(Element {
+            raw: unquote(s, element),
+            params,
+        });
+    }
+    Ok(Header {
+        raw: unquote(s, trim(s, 0..s.len())),
+        elements,
+    })
+}
+
+/// Parses a value with the regular deserializer.
+fn value<'de, 'a, T, F>(value: &'de Value, config: &'a Config, f: F) -> Result<T, Error>
+where
+    F: FnOnce(ConfiguredDeserializer<'de, 'a>) -> Result<T, Error>,
+{
+    f(Deserializer::with_config(&value.text, config))
+        .map_err(|error| error.shifted(value.span.start))
+}
+
+macro_rules! forward_to_value {
+    ($field:ident => $($func:ident)*) => {
+        $(
+            fn $func<V>(self, visitor: V) -> Result<V::Value, Error>
+            where
+                V: Visitor<'de>,
+            {
+                value(&self.$field.raw, self.config, |de| de::Deserializer::$func(de, visitor))
+            }
+        )*
+    };
+}
+
+/// Deserializes an entire header value.
+struct HeaderDeserializer<'de, 'a> {
+    header: &'de Header,
+    config: &'a Config,
+    skipped: &'a Skipped,
+}
+
+impl<'de, 'a> de::Deserializer<'de> for HeaderDeserializer<'de, 'a> {
+    type Error = Error;
+
+    forward_to_value!(header => deserialize_any deserialize_bool deserialize_i8 deserialize_i16
+        deserialize_i32 deserialize_i64 deserialize_i128 deserialize_u8 deserialize_u16
+        deserialize_u32 deserialize_u64 deserialize_u128 deserialize_f32 deserialize_f64
+        deserialize_char deserialize_str deserialize_string deserialize_bytes
+        deserialize_byte_buf deserialize_unit deserialize_identifier
+        deserialize_ignored_any);
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        if self.header.elements.is_empty() {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_unit_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        value(&self.header.raw, self.config, |de| {
+            de::Deserializer::deserialize_unit_struct(de, name, visitor)
+        })
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_seq(ListAccess {
+            elements: self.header.elements.iter().enumerate(),
+            config: self.config,
+            skipped: self.skipped,
+        })
+    }
+
+    fn deserialize_tuple<V>(self, len: usize, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.single()?.deserialize_tuple(len, visitor)
+    }
+
+    fn deserialize_tuple_struct<V>(
+        self,
+        name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.single()?.deserialize_tuple_struct(name, len, visitor)
+    }
+
+    fn deserialize_map<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_map(self.directives(None))
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_map(self.directives(Some(fields)))
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        value(&self.header.raw, self.config, |de| {
+            de::Deserializer::deserialize_enum(de, name, variants, visitor)
+        })
+    }
+}
+
+impl<'de, 'a> HeaderDeserializer<'de, 'a> {
+    /// Returns the parameters of all elements as directives.
+    fn directives(self, fields: Option<&'static [&'static str]>) -> ParamAccess<'de, 'a> {
+        let params = self
+            .header
+            .elements
+            .iter()
+            .flat_map(|element| &element.params);
+        ParamAccess::new(params.collect(), fields, self.config, self.skipped)
+    }
+
+    /// Returns the only element of the header.
+    fn single(self) -> Result<ElementDeserializer<'de, 'a>, Error> {
+        match self.header.elements[..] {
+            [ref element] => Ok(ElementDeserializer {
+                element,
+                config: self.config,
+                skipped: self.skipped,
+            }),
+            ref elements => Err(Error::Message(format!(
+                "expected a single element, found {}",
+                elements.len()
+            ))),
+        }
+    }
+}
+
+/// Feeds the elements of a list to a visitor.
+struct ListAccess<'de, 'a> {
+    elements: std::iter::Enumerate<std::slice::Iter<'de, Element>>,
+    config: &'a Config,
+    skipped: &'a Skipped,
+}
+
+impl<'de, 'a> de::SeqAccess<'de> for ListAccess<'de, 'a> {
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Error>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        match self.elements.next() {
+            Some((idx, element)) => seed
+                .deserialize(ElementDeserializer {
+                    element,
+                    config: self.config,
+                    skipped: self.skipped,
+                })
+                .map(Some)
+                .map_err(|error| error.located(&format!("[{}]", idx), element.raw.span.clone())),
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.elements.len())
+    }
+}
+
+/// Deserializes a single element of a list.
+struct ElementDeserializer<'de, 'a> {
+    element: &'de Element,
+    config: &'a Config,
+    skipped: &'a Skipped,
+}
+
+impl<'de, 'a> de::Deserializer<'de> for ElementDeserializer<'de, 'a> {
+    type Error = Error;
+
+    forward_to_value!(element => deserialize_any deserialize_bool deserialize_i8 deserialize_i16
+        deserialize_i32 deserialize_i64 deserialize_i128 deserialize_u8 deserialize_u16
+        deserialize_u32 deserialize_u64 deserialize_u128 deserialize_f32 deserialize_f64
+        deserialize_char deserialize_str deserialize_string deserialize_bytes
+        deserialize_byte_buf deserialize_unit deserialize_seq deserialize_identifier
+        deserialize_ignored_any);
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_unit_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        value(&self.element.raw, self.config, |de| {
+            de::Deserializer::deserialize_unit_struct(de, name, visitor)
+        })
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_tuple<V>(self, len: usize, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        if len != 2 {
+            return Err(Error::Message(
+                "expected a tuple of a value and its parameters".into(),
+            ));
+        }
+        // `split` drops empty parts, so an element of only semicolons has
+        // no leading value.
+        if self.element.params.is_empty() {
+            return Err(Error::Message("missing value before the parameters".into())
+                .located("[0]", self.element.raw.span.clone()));
+        }
+        visitor.visit_seq(HeadAccess {
+            element: self.element,
+            idx: 0,
+            config: self.config,
+            skipped: self.skipped,
+        })
+    }
+
+    fn deserialize_tuple_struct<V>(
+        self,
+        _name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_tuple(len, visitor)
+    }
+
+    fn deserialize_map<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_map(self.params(None))
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_map(self.params(Some(fields)))
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        value(&self.element.raw, self.config, |de| {
+            de::Deserializer::deserialize_enum(de, name, variants, visitor)
+        })
+    }
+}
+
+impl<'de, 'a> ElementDeserializer<'de, 'a> {
+    /// Returns the parameters of the element.
+    fn params(self, fields: Option<&'static [&'static str]>) -> ParamAccess<'de, 'a> {
+        let params = self.element.params.iter().collect();
+        ParamAccess::new(params, fields, self.config, self.skipped)
+    }
+}
+
+/// Feeds the leading value of an element and its parameters to a
+/// visitor.
+struct HeadAccess<'de, 'a> {
+    element: &'de Element,
+    idx: usize,
+    config: &'a Config,
+    skipped: &'a Skipped,
+}
+
+impl<'de, 'a> de::SeqAccess<'de> for HeadAccess<'de, 'a> {
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Error>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        self.idx += 1;
+        let params = &self.element.params;
+        match self.idx {
+            1 => value(&params[0].head, self.config, |de| seed.deserialize(de)).map(Some),
+            2 => seed
+                .deserialize(ParamsDeserializer {
+                    params: &params[1..],
+                    config: self.config,
+                    skipped: self.skipped,
+                })
+                .map(Some),
+            _ => Ok(None),
+        }
+    }
+}
+
+/// Deserializes the parameters of an element.
+struct ParamsDeserializer<'de, 'a> {
+    params: &'de [Param],
+    config: &'a Config,
+    skipped: &'a Skipped,
+}
+
+impl<'de, 'a> de::Deserializer<'de> for ParamsDeserializer<'de, 'a> {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        let params = self.params.iter().collect();
+        visitor.visit_map(ParamAccess::new(params, None, self.config, self.skipped))
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        let params = self.params.iter().collect();
+        visitor.visit_map(ParamAccess::new(
+            params,
+            Some(fields),
+            self.config,
+            self.skipped,
+        ))
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        if self.params.is_empty() {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct newtype_struct seq tuple
+        tuple_struct map enum identifier ignored_any
+    }
+}
+
+/// Feeds parameters or directives to a visitor.
+struct ParamAccess<'de, 'a> {
+    params: std::vec::IntoIter<&'de Param>,
+    left_out: LeftOutFields<'a>,
+    value: Option<Entry<&'de Param>>,
+    config: &'a Config,
+}
+
+impl<'de, 'a> ParamAccess<'de, 'a> {
+    /// Creates an access that fills in the left out fields of a struct
+    /// with the given fields after the parameters.
+    fn new(
+        params: Vec<&'de Param>,
+        fields: Option<&'static [&'static str]>,
+        config: &'a Config,
+        skipped: &'a Skipped,
+    ) -> Self {
+        ParamAccess {
+            params: params.into_iter(),
+            left_out: LeftOutFields::new(fields, skipped),
+            value: None,
+            config,
+        }
+    }
+}
+
+impl<'de, 'a> de::MapAccess<'de> for ParamAccess<'de, 'a> {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Error>
+    where
+        K: de::DeserializeSeed<'de>,
+    {
+        match self.params.next() {
+            Some(param) => {
+                self.left_out.given(&param.name);
+                self.value = Some(Entry::Given(param));
+                seed.deserialize(Deserializer::with_config(&param.name, self.config))
+                    .map(Some)
+            }
+            None => match self.left_out.next() {
+                Some(field) => {
+                    self.value = Some(Entry::LeftOut(field));
+                    seed.deserialize(Deserializer::with_config(field, self.config))
+                        .map(Some)
+                }
+                None => Ok(None),
+            },
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Error>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        match self.value.take() {
+            Some(Entry::LeftOut(field)) => {
+                let value = seed.deserialize(MissingDeserializer { field })?;
+                self.left_out.filled();
+                Ok(value)
+            }
+            Some(Entry::Given(param)) => {
+                value(&param.value, self.config, |de| seed.deserialize(de))
+                    .map_err(|error| error.located(&param.name, param.value.span.clone()))
+            }
+            None => Err(Error::Message("value requested before key".into())),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.params.len() + self.left_out.len())
+    }
+}
+
+/// Writes a value as a quoted-string.
+fn quoted(value: &str) -> String {
+    let mut rv = String::with_capacity(value.len() + 2);
+    rv.push('"');
+    for c in value.chars() {
+        if c == '"' || c == '\\' {
+            rv.push('\\');
+        }
+        rv.push(c);
+    }
+    rv.push('"');
+    rv
+}
+
+/// Quotes list elements that would otherwise be split or trimmed.
+fn quote_element(value: String) -> String {
+    if value.is_empty() || value.trim() != value || value.contains([',', ';', '"', '\\']) {
+        quoted(&value)
+    } else {
+        value
+    }
+}
+
+/// Quotes parameter values that are not tokens.
+fn quote_param(value: &str) -> String {
+    if !value.is_empty() && value.chars().all(is_tchar) {
+        value.to_string()
+    } else {
+        quoted(value)
+    }
+}
+
+/// Serializes an entire header value.
+struct HeaderSerializer<'a> {
+    config: &'a Config,
+}
+
+/// Serializes a single element of a list.
+struct ElementSerializer<'a> {
+    config: &'a Config,
+}
+
+macro_rules! serialize_scalars {
+    ($finish:expr => $($ty:ty => $meth:ident,)*) => {
+        $(fn $meth(self, v: $ty) -> Result<String, Error> {
+            ser::Serializer::$meth(self.plain(), v).map($finish)
+        })*
+    };
+}
+
+/// Implements the parts that both serializers share.
+macro_rules! impl_serializer {
+    ($ty:ident, $finish:expr, $seq:ty, $params_separator:expr) => {
+        impl<'a> $ty<'a> {
+            fn plain(&self) -> ConfiguredSerializer<'a> {
+                Serializer::with_config(self.config)
+            }
+        }
+
+        impl<'a> ser::Serializer for $ty<'a> {
+            type Ok = String;
+            type Error = Error;
+            type SerializeSeq = $seq;
+            type SerializeTuple = ElementTupleSerializer<'a>;
+            type SerializeTupleStruct = ElementTupleSerializer<'a>;
+            type SerializeTupleVariant = ser::Impossible<String, Error>;
+            type SerializeMap = ParamsSerializer<'a>;
+            type SerializeStruct = ParamsSerializer<'a>;
+            type SerializeStructVariant = ser::Impossible<String, Error>;
+
+            serialize_scalars! {
+                $finish =>
+                bool => serialize_bool,
+                u8  => serialize_u8,
+                u16 => serialize_u16,
+                u32 => serialize_u32,
+                u64 => serialize_u64,
+                u128 => serialize_u128,
+                i8  => serialize_i8,
+                i16 => serialize_i16,
+                i32 => serialize_i32,
+                i64 => serialize_i64,
+                i128 => serialize_i128,
+                f32 => serialize_f32,
+                f64 => serialize_f64,
+                char => serialize_char,
+                &str => serialize_str,
+                &[u8] => serialize_bytes,
+            }
+
+            fn serialize_unit(self) -> Result<String, Error> {
+                ser::Serializer::serialize_unit(self.plain()).map($finish)
+            }
+
+            fn serialize_unit_struct(self, name: &'static str) -> Result<String, Error> {
+                ser::Serializer::serialize_unit_struct(self.plain(), name).map($finish)
+            }
+
+            fn serialize_unit_variant(
+                self,
+                name: &'static str,
+                variant_index: u32,
+                variant: &'static str,
+            ) -> Result<String, Error> {
+                ser::Serializer::serialize_unit_variant(self.plain(), name, variant_index, variant)
+                    .map($finish)
+            }
+
+            fn serialize_newtype_struct<T: ?Sized + ser::Serialize>(
+                self,
+                _name: &'static str,
+                value: &T,
+            ) -> Result<String, Error> {
+                value.serialize(self)
+            }
+
+            fn serialize_newtype_variant<T: ?Sized + ser::Serialize>(
+                self,
+                name: &'static str,
+                variant_index: u32,
+                variant: &'static str,
+                value: &T,
+            ) -> Result<String, Error> {
+                ser::Serializer::serialize_newtype_variant(
+                    self.plain(),
+                    name,
+                    variant_index,
+                    variant,
+                    value,
+                )
+                .map($finish)
+            }
+
+            fn serialize_none(self) -> Result<String, Error> {
+                Ok(String::new())
+            }
+
+            fn serialize_some<T: ?Sized + ser::Serialize>(
+                self,
+                value: &T,
+            ) -> Result<String, Error> {
+                value.serialize(self)
+            }
+
+            fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Error> {
+                self.seq(len)
+            }
+
+            fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Error> {
+                if len != 2 {
+                    return Err(Error::Message(
+                        "expected a tuple of a value and its parameters".into(),
+                    ));
+                }
+                Ok(ElementTupleSerializer {
+                    config: self.config,
+                    parts: Vec::new(),
+                })
+            }
+
+            fn serialize_tuple_struct(
+                self,
+                _name: &'static str,
+                len: usize,
+            ) -> Result<Self::SerializeTupleStruct, Error> {
+                ser::Serializer::serialize_tuple(self, len)
+            }
+
+            fn serialize_tuple_variant(
+                self,
+                _name: &'static str,
+                _variant_index: u32,
+                _variant: &'static str,
+                _len: usize,
+            ) -> Result<Self::SerializeTupleVariant, Error> {
+                Err(Error::ImpossibleSerialization("tuple variant"))
+            }
+
+            fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Error> {
+                Ok(ParamsSerializer {
+                    config: self.config,
+                    separator: $params_separator,
+                    params: Vec::new(),
+                    key: None,
+                })
+            }
+
+            fn serialize_struct(
+                self,
+                _name: &'static str,
+                len: usize,
+            ) -> Result<Self::SerializeStruct, Error> {
+                ser::Serializer::serialize_map(self, Some(len))
+            }
+
+            fn serialize_struct_variant(
+                self,
+                _name: &'static str,
+                _variant_index: u32,
+                _variant: &'static str,
+                _len: usize,
+            ) -> Result<Self::SerializeStructVariant, Error> {
+                Err(Error::ImpossibleSerialization("struct variant"))
+            }
+        }
+    };
+}
+
+impl_serializer!(HeaderSerializer, |value| value, ListSerializer<'a>, ", ");
+impl_serializer!(ElementSerializer, quote_element, SeqSerializer<'a>, ";");
+
+impl<'a> HeaderSerializer<'a> {
+    fn seq(self, _len: Option<usize>) -> Result<ListSerializer<'a>, Error> {
+        Ok(ListSerializer {
+            config: self.config,
+            elements: Vec::new(),
+        })
+    }
+}
+
+impl<'a> ElementSerializer<'a> {
+    fn seq(self, len: Option<usize>) -> Result<SeqSerializer<'a>, Error> {
+        ser::Serializer::serialize_seq(self.plain(), len)
+    }
+}
+
+/// Serializes the elements of a list.
+struct ListSerializer<'a> {
+    config: &'a Config,
+    elements: Vec<String>,
+}
+
+impl<'a> ser::SerializeSeq for ListSerializer<'a> {
+    type Ok = String;
+    type Error = Error;
+
+    fn serialize_element<T: ?Sized + ser::Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        let element = value.serialize(ElementSerializer {
+            config: self.config,
+        })?;
+        self.elements.push(element);
+        Ok(())
+    }
+
+    fn end(self) -> Result<String, Error> {
+        Ok(self.elements.join(", "))
+    }
+}
+
+/// Serializes an element with its parameters.
+struct ElementTupleSerializer<'a> {
+    config: &'a Config,
+    parts: Vec<String>,
+}
+
+impl<'a> ser::SerializeTuple for ElementTupleSerializer<'a> {
+    type Ok = String;
+    type Error = Error;
+
+    fn serialize_element<T: ?Sized + ser::Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        let part = value.serialize(ElementSerializer {
+            config: self.config,
+        })?;
+        self.parts.push(part);
+        Ok(())
+    }
+
+    fn end(self) -> Result<String, Error> {
+        Ok(self
+            .parts
+            .into_iter()
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join(";"))
+    }
+}
+
+impl<'a> ser::SerializeTupleStruct for ElementTupleSerializer<'a> {
+    type Ok = String;
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + ser::Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        ser::SerializeTuple::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<String, Error> {
+        ser::SerializeTuple::end(self)
+    }
+}
+
+/// Serializes parameters or directives.
+struct ParamsSerializer<'a> {
+    config: &'a Config,
+    separator: &'static str,
+    params: Vec<String>,
+    key: Option<String>,
+}
+
+impl<'a> ser::SerializeMap for ParamsSerializer<'a> {
+    type Ok = String;
+    type Error = Error;
+
+    fn serialize_key<T: ?Sized + ser::Serialize>(&mut self, key: &T) -> Result<(), Error> {
+        self.key = Some(key.serialize(Serializer::with_config(self.config))?);
+        Ok(())
+    }
+
+    fn serialize_value<T: ?Sized + ser::Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        let key = self
+            .key
+            .take()
+            .ok_or_else(|| Error::Message("value serialized before key".into()))?;
+        if key.is_empty() || !key.chars().all(is_tchar) {
+            return Err(Error::Message(format!("invalid parameter name `{}`", key)));
+        }
+        value.serialize(ParamSerializer {
+            params: &mut self.params,
+            name: key,
+            config: self.config,
+        })
+    }
+
+    fn end(self) -> Result<String, Error> {
+        Ok(self.params.join(self.separator))
+    }
+}
+
+impl<'a> ser::SerializeStruct for ParamsSerializer<'a> {
+    type Ok = String;
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + ser::Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        ser::SerializeMap::serialize_entry(self, key, value)
+    }
+
+    fn end(self) -> Result<String, Error> {
+        ser::SerializeMap::end(self)
+    }
+}
+
+/// Serializes the value of a parameter.
+///
+/// `true` is written as the bare name and `false` and `None` are left
+/// out.  Everything else, including sequences and structs, is written as a
+/// single value by the plain serializer, which is how parameters are read.
+struct ParamSerializer<'a, 'b> {
+    params: &'b mut Vec<String>,
+    name: String,
+    config: &'a Config,
+}
+
+impl<'a, 'b> ParamSerializer<'a, 'b> {
+    fn plain(&self) -> ConfiguredSerializer<'a> {
+        Serializer::with_config(self.config)
+    }
+}
+
+impl<'a, 'b> Sink for ParamSerializer<'a, 'b> {
+    fn push(self, value: Result<String, Error>) -> Result<(), Error> {
+        let value = value.map_err(|error| self.wrap(error))?;
+        self.params
+            .push(format!("{}={}", self.name, quote_param(&value)));
+        Ok(())
+    }
+
+    fn wrap(&self, error: Error) -> Error {
+        wrap_position(&self.name, None, error)
+    }
+}
+
+macro_rules! serialize_as_param {
+    ($($ty:ty => $meth:ident,)*) => {
+        $(fn $meth(self, v: $ty) -> Result<(), Error> {
+            let value = ser::Serializer::$meth(self.plain(), v);
+            self.push(value)
+        })*
+    };
+}
+
+impl<'a, 'b> ser::Serializer for ParamSerializer<'a, 'b> {
+    type Ok = ();
+    type Error = Error;
+    type SerializeSeq = Leaf<ParamSerializer<'a, 'b>, SeqSerializer<'a>>;
+    type SerializeTuple = Leaf<ParamSerializer<'a, 'b>, TupleSerializer<'a>>;
+    type SerializeTupleStruct = Leaf<ParamSerializer<'a, 'b>, TupleSerializer<'a>>;
+    type SerializeTupleVariant = ser::Impossible<(), Error>;
+    type SerializeMap = Leaf<ParamSerializer<'a, 'b>, MapSerializer<'a>>;
+    type SerializeStruct = Leaf<ParamSerializer<'a, 'b>, MapSerializer<'a>>;
+    type SerializeStructVariant = ser::Impossible<(), Error>;
+
+    serialize_as_param! {
+        u8  => serialize_u8,
+        u16 => serialize_u16,
+        u32 => serialize_u32,
+        u64 => serialize_u64,
+        u128 => serialize_u128,
+        i8  => serialize_i8,
+        i16 => serialize_i16,
+        i32 => serialize_i32,
+        i64 => serialize_i64,
+        i128 => serialize_i128,
+        f32 => serialize_f32,
+        f64 => serialize_f64,
+        char => serialize_char,
+        &str => serialize_str,
+        &[u8] => serialize_bytes,
+    }
+
+    fn serialize_bool(self, v: bool) -> Result<(), Error> {
+        // a bare name reads as `true` and a left out one as `false`.
+        if v {
+            self.params.push(self.name);
+        }
+        Ok(())
+    }
+
+    fn serialize_unit(self) -> Result<(), Error> {
+        let value = ser::Serializer::serialize_unit(self.plain());
+        self.push(value)
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<(), Error> {
+        let value = ser::Serializer::serialize_unit_struct(self.plain(), name);
+        self.push(value)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+    ) -> Result<(), Error> {
+        let value =
+            ser::Serializer::serialize_unit_variant(self.plain(), name, variant_index, variant);
+        self.push(value)
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + ser::Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + ser::Serialize>(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        let value = ser::Serializer::serialize_newtype_variant(
+            self.plain(),
+            name,
+            variant_index,
+            variant,
+            value,
+        );
+        self.push(value)
+    }
+
+    fn serialize_none(self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn serialize_some<T: ?Sized + ser::Serialize>(self, value: &T) -> Result<(), Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Error> {
+        let inner = ser::Serializer::serialize_seq(self.plain(), len);
+        Leaf::new(self, inner)
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Error> {
+        let inner = ser::Serializer::serialize_tuple(self.plain(), len);
+        Leaf::new(self, inner)
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Error> {
+        let inner = ser::Serializer::serialize_tuple_struct(self.plain(), name, len);
+        Leaf::new(self, inner)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Error> {
+        Err(self.wrap(Error::ImpossibleSerialization("tuple variant")))
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, Error> {
+        let inner = ser::Serializer::serialize_map(self.plain(), len);
+        Leaf::new(self, inner)
+    }
+
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, Error> {
+        let inner = ser::Serializer::serialize_struct(self.plain(), name, len);
+        Leaf::new(self, inner)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Error> {
+        Err(self.wrap(Error::ImpossibleSerialization("struct variant")))
+    }
+}
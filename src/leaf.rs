@@ -0,0 +1,137 @@
+use serde::ser;
+
+use crate::error::Error;
+
+/// Receives the plain representation of a value that is written as a
+/// single unit, like one environment variable or one argument.
+pub(crate) trait Sink: Sized {
+    /// Adds the serialized value.
+    fn push(self, value: Result<String, Error>) -> Result<(), Error>;
+
+    /// Attaches the location of the sink to an error.
+    fn wrap(&self, error: Error) -> Error;
+}
+
+/// Serializes a compound value with the plain serializer and hands the
+/// result to a sink.
+pub(crate) struct Leaf<K, S> {
+    sink: K,
+    inner: S,
+}
+
+impl<K: Sink, S> Leaf<K, S> {
+    pub(crate) fn new(sink: K, inner: Result<S, Error>) -> Result<Leaf<K, S>, Error> {
+        match inner {
+            Ok(inner) => Ok(Leaf { sink, inner }),
+            Err(error) => Err(sink.wrap(error)),
+        }
+    }
+
+    fn check(&self, rv: Result<(), Error>) -> Result<(), Error> {
+        rv.map_err(|error| self.sink.wrap(error))
+    }
+}
+
+impl<K, S> ser::SerializeSeq for Leaf<K, S>
+where
+    K: Sink,
+    S: ser::SerializeSeq<Ok = String, Error = Error>,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: ?Sized + ser::Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        let rv = self.inner.serialize_element(value);
+        self.check(rv)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        let value = self.inner.end();
+        self.sink.push(value)
+    }
+}
+
+impl<K, S> ser::SerializeTuple for Leaf<K, S>
+where
+    K: Sink,
+    S: ser::SerializeTuple<Ok = String, Error = Error>,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: ?Sized + ser::Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        let rv = self.inner.serialize_element(value);
+        self.check(rv)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        let value = self.inner.end();
+        self.sink.push(value)
+    }
+}
+
+impl<K, S> ser::SerializeTupleStruct for Leaf<K, S>
+where
+    K: Sink,
+    S: ser::SerializeTupleStruct<Ok = String, Error = Error>,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + ser::Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        let rv = self.inner.serialize_field(value);
+        self.check(rv)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        let value = self.inner.end();
+        self.sink.push(value)
+    }
+}
+
+impl<K, S> ser::SerializeMap for Leaf<K, S>
+where
+    K: Sink,
+    S: ser::SerializeMap<Ok = String, Error = Error>,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<T: ?Sized + ser::Serialize>(&mut self, key: &T) -> Result<(), Error> {
+        let rv = self.inner.serialize_key(key);
+        self.check(rv)
+    }
+
+    fn serialize_value<T: ?Sized + ser::Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        let rv = self.inner.serialize_value(value);
+        self.check(rv)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        let value = self.inner.end();
+        self.sink.push(value)
+    }
+}
+
+impl<K, S> ser::SerializeStruct for Leaf<K, S>
+where
+    K: Sink,
+    S: ser::SerializeStruct<Ok = String, Error = Error>,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + ser::Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        let rv = self.inner.serialize_field(key, value);
+        self.check(rv)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        let value = self.inner.end();
+        self.sink.push(value)
+    }
+}
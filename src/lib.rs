@@ -40,11 +40,50 @@
 //! ```rust
 //! assert_eq!(serde_plain::to_string(&true).unwrap(), "true");
 //! ```
+//!
+//! # Configuration
+//!
+//! The representation can be customized with a [`Config`] which is passed
+//! to [`to_string_with_config`] and [`from_str_with_config`]:
+//!
+//! ```rust
+//! use serde_plain::{Config, FloatFormat};
+//!
+//! let config = Config::new().float_format(FloatFormat::Scientific);
+//! assert_eq!(serde_plain::to_string_with_config(&1500.0, &config).unwrap(), "1.5e3");
+//! ```
+mod args;
+#[cfg(feature = "bitflags")]
+pub mod bitflags;
+mod bytes;
+mod canonical;
+mod config;
 mod de;
+mod env;
 mod error;
+mod flat;
+mod float;
+pub mod http_header;
+mod leaf;
+mod lines;
+pub mod logfmt;
 mod macros;
+mod missing;
+pub mod openapi;
+mod pattern;
+mod prefix;
+pub mod properties;
+mod quote;
 mod ser;
+mod url;
 
+pub use crate::args::*;
+pub use crate::canonical::*;
+pub use crate::config::*;
 pub use crate::de::*;
+pub use crate::env::*;
 pub use crate::error::*;
+pub use crate::lines::*;
+pub use crate::prefix::*;
 pub use crate::ser::*;
+pub use crate::url::*;
@@ -0,0 +1,162 @@
+use std::io::{BufRead, Write};
+use std::marker::PhantomData;
+
+use serde::de::DeserializeOwned;
+use serde::ser::Serialize;
+
+use crate::config::Config;
+use crate::de::Deserializer;
+use crate::error::Error;
+use crate::ser::Serializer;
+
+/// Deserialize one value per line from a reader.
+///
+/// This returns an iterator over the values.  Errors carry the number of
+/// the line they occurred on ([`Error::line`]) and reading stops after an
+/// io error.  Line endings (`\n` or `\r\n`) are not part of the value.
+///
+/// ```rust
+/// let input = "1\n2\nx\n";
+/// let mut lines = serde_plain::from_reader_lines::<_, u32>(input.as_bytes());
+/// assert_eq!(lines.next().unwrap().unwrap(), 1);
+/// assert_eq!(lines.next().unwrap().unwrap(), 2);
+/// assert_eq!(lines.next().unwrap().unwrap_err().line(), Some(3));
+/// assert!(lines.next().is_none());
+/// ```
+pub fn from_reader_lines<R, T>(reader: R) -> LinesDeserializer<R, T>
+where
+    R: BufRead,
+    T: DeserializeOwned,
+{
+    from_reader_lines_with_config(reader, &Config::default())
+}
+
+/// Like [`from_reader_lines`] but with a custom configuration.
+pub fn from_reader_lines_with_config<R, T>(reader: R, config: &Config) -> LinesDeserializer<R, T>
+where
+    R: BufRead,
+    T: DeserializeOwned,
+{
+    LinesDeserializer {
+        reader,
+        config: config.clone(),
+        buf: String::new(),
+        line: 0,
+        done: false,
+        _marker: PhantomData,
+    }
+}
+
+/// An iterator deserializing one value per line.
+///
+/// This is created by [`from_reader_lines`].  The line buffer and the
+/// configuration are reused for every line, so the only allocations per
+/// line are those of the values themselves.
+pub struct LinesDeserializer<R, T> {
+    reader: R,
+    config: Config,
+    buf: String,
+    line: usize,
+    done: bool,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<R, T> LinesDeserializer<R, T> {
+    /// Returns the number of the last line that was read.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+}
+
+impl<R, T> Iterator for LinesDeserializer<R, T>
+where
+    R: BufRead,
+    T: DeserializeOwned,
+{
+    type Item = Result<T, Error>;
+
+    fn next(&mut self) -> Option<Result<T, Error>> {
+        if self.done {
+            return None;
+        }
+        self.buf.clear();
+        match self.reader.read_line(&mut self.buf) {
+            Ok(0) => {
+                self.done = true;
+                return None;
+            }
+            Ok(_) => self.line += 1,
+            Err(err) => {
+                self.done = true;
+                return Some(Err(Error::Line {
+                    line: self.line + 1,
+                    error: Box::new(err.into()),
+                }));
+            }
+        }
+
+        let mut value = self.buf.as_str();
+        value = value.strip_suffix('\n').unwrap_or(value);
+        value = value.strip_suffix('\r').unwrap_or(value);
+        Some(
+            T::deserialize(Deserializer::with_config(value, &self.config)).map_err(|err| {
+                Error::Line {
+                    line: self.line,
+                    error: Box::new(err),
+                }
+            }),
+        )
+    }
+}
+
+/// Serialize each value on a line of its own into a writer.
+///
+/// Every line is terminated with `\n`.  Values whose representation
+/// contains a line break cannot be written.
+///
+/// ```rust
+/// let mut out = Vec::new();
+/// serde_plain::to_writer_lines(&mut out, &[1, 2, 3]).unwrap();
+/// assert_eq!(out, b"1\n2\n3\n");
+/// ```
+pub fn to_writer_lines<'a, W, I, T>(writer: W, values: I) -> Result<(), Error>
+where
+    W: Write,
+    I: IntoIterator<Item = &'a T>,
+    T: Serialize + 'a,
+{
+    to_writer_lines_with_config(writer, values, &Config::default())
+}
+
+/// Like [`to_writer_lines`] but with a custom configuration.
+pub fn to_writer_lines_with_config<'a, W, I, T>(
+    mut writer: W,
+    values: I,
+    config: &Config,
+) -> Result<(), Error>
+where
+    W: Write,
+    I: IntoIterator<Item = &'a T>,
+    T: Serialize + 'a,
+{
+    for (idx, value) in values.into_iter().enumerate() {
+        let line_error = |error| Error::Line {
+            line: idx + 1,
+            error: Box::new(error),
+        };
+        let line = value
+            .serialize(Serializer::with_config(config))
+            .map_err(line_error)?;
+        if line.contains(['\n', '\r']) {
+            return Err(line_error(Error::Message(format!(
+                "`{}` contains a line break",
+                line.escape_default()
+            ))));
+        }
+        writer
+            .write_all(line.as_bytes())
+            .and_then(|_| writer.write_all(b"\n"))
+            .map_err(|err| line_error(err.into()))?;
+    }
+    Ok(())
+}
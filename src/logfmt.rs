@@ -0,0 +1,204 @@
+//! Reading and writing logfmt lines.
+//!
+//! A logfmt line is a sequence of `key=value` pairs separated by spaces,
+//! like `level=info msg="server started" port=8080`.  Values are quoted
+//! with double quotes if they are empty or contain spaces, `=`, quotes or
+//! control characters, quoted values support the escapes `\"`, `\\`, `\n`,
+//! `\r`, `\t` and `\uXXXX`.  A key without a value is read as `true`.
+//!
+//! ```rust
+//! # #[macro_use] extern crate serde_derive;
+//! # fn main() {
+//! #[derive(Deserialize, Serialize, Debug, PartialEq)]
+//! #[serde(rename_all = "lowercase")]
+//! pub enum Level {
+//!     Info,
+//!     Error,
+//! }
+//!
+//! #[derive(Deserialize, Serialize, Debug, PartialEq)]
+//! pub struct Record {
+//!     level: Level,
+//!     msg: String,
+//!     port: u16,
+//! }
+//!
+//! let line = r#"level=info msg="server started" port=8080"#;
+//! let record: Record = serde_plain::logfmt::from_str(line).unwrap();
+//! assert_eq!(record.level, Level::Info);
+//! assert_eq!(serde_plain::logfmt::to_string(&record).unwrap(), line);
+//! # }
+//! ```
+//!
+//! Every value is parsed and formatted like [`from_str`](crate::from_str)
+//! and [`to_string`](crate::to_string) do.  Structs and maps with plain
+//! values are supported, nested structs use dotted keys such as
+//! `db.port`.  Errors for values carry the key and the byte range of the
+//! value (`Error::Located`).
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use serde::de::DeserializeOwned;
+use serde::ser;
+
+use crate::config::Config;
+use crate::error::Error;
+use crate::flat::{
+    dotted_key, wrap_position, Entry, FlatDeserializer, FlatSerializer, Position, Scheme,
+};
+
+/// Deserialize a struct or map from a logfmt line.
+pub fn from_str<T: DeserializeOwned>(s: &str) -> Result<T, Error> {
+    from_str_with_config(s, &Config::default())
+}
+
+/// Like [`from_str`] but with a custom configuration.
+pub fn from_str_with_config<T: DeserializeOwned>(s: &str, config: &Config) -> Result<T, Error> {
+    let entries = parse(s)?;
+    T::deserialize(FlatDeserializer::new(&entries, "", config, &LOGFMT))
+}
+
+/// Serialize a struct or map into a logfmt line.
+///
+/// Fields that are `None` are left out.  The line is not terminated by a
+/// newline.
+pub fn to_string<T: ser::Serialize>(value: &T) -> Result<String, Error> {
+    to_string_with_config(value, &Config::default())
+}
+
+/// Like [`to_string`] but with a custom configuration.
+pub fn to_string_with_config<T: ser::Serialize>(
+    value: &T,
+    config: &Config,
+) -> Result<String, Error> {
+    let mut pairs = Vec::new();
+    value.serialize(FlatSerializer::new(&mut pairs, "", config, &LOGFMT))?;
+    let mut rv = String::new();
+    for (key, value) in pairs {
+        if key.is_empty() || key.chars().any(needs_quotes) {
+            return Err(Error::Message(format!("invalid logfmt key `{}`", key)));
+        }
+        if !rv.is_empty() {
+            rv.push(' ');
+        }
+        rv.push_str(&key);
+        rv.push('=');
+        if value.is_empty() || value.chars().any(needs_quotes) {
+            quote(&mut rv, &value);
+        } else {
+            rv.push_str(&value);
+        }
+    }
+    Ok(rv)
+}
+
+/// Keys are joined with dots.
+static LOGFMT: Scheme = Scheme {
+    key: dotted_key,
+    wrap: wrap_position,
+    flatten_maps: true,
+};
+
+fn needs_quotes(c: char) -> bool {
+    c == ' ' || c == '=' || c == '"' || c == '\\' || c.is_control()
+}
+
+fn quote(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => {
+                write!(out, "\\u{:04x}", c as u32).unwrap();
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+fn is_whitespace(c: char) -> bool {
+    c == ' ' || c == '\t' || c == '\r' || c == '\n'
+}
+
+/// Parses a logfmt line into its entries.  Later keys replace earlier
+/// ones.
+fn parse(s: &str) -> Result<BTreeMap<String, Entry>, Error> {
+    let mut entries = BTreeMap::new();
+    let mut pos = 0;
+    loop {
+        pos += s[pos..].len() - s[pos..].trim_start_matches(is_whitespace).len();
+        if pos == s.len() {
+            break;
+        }
+        let key_len = s[pos..]
+            .find(|c| c == '=' || is_whitespace(c))
+            .unwrap_or(s.len() - pos);
+        let key = &s[pos..pos + key_len];
+        if key.is_empty() || key.contains('"') {
+            return Err(Error::Message(format!("expected a key at offset {}", pos)));
+        }
+        pos += key_len;
+
+        let (value, offset, len) = if s[pos..].starts_with('=') {
+            pos += 1;
+            let (value, len) = if s[pos..].starts_with('"') {
+                unquote(s, pos)?
+            } else {
+                let len = s[pos..].find(is_whitespace).unwrap_or(s.len() - pos);
+                (s[pos..pos + len].to_string(), len)
+            };
+            let offset = pos;
+            pos += len;
+            (value, offset, len)
+        } else {
+            ("true".to_string(), pos - key_len, key_len)
+        };
+        entries.insert(
+            key.to_string(),
+            Entry {
+                value,
+                position: Some(Position::Span { offset, len }),
+            },
+        );
+    }
+    Ok(entries)
+}
+
+/// Parses the quoted value starting at `start`, returning the value and
+/// the length of the quoted value in the input.
+fn unquote(s: &str, start: usize) -> Result<(String, usize), Error> {
+    let mut rv = String::new();
+    let mut chars = s[start + 1..].char_indices();
+    while let Some((idx, c)) = chars.next() {
+        match c {
+            '"' => return Ok((rv, idx + 2)),
+            '\\' => match chars.next() {
+                Some((_, 'n')) => rv.push('\n'),
+                Some((_, 'r')) => rv.push('\r'),
+                Some((_, 't')) => rv.push('\t'),
+                Some((_, 'u')) => {
+                    let escape = start + 1 + idx;
+                    let c = s
+                        .get(escape + 2..escape + 6)
+                        .filter(|digits| digits.bytes().all(|b| b.is_ascii_hexdigit()))
+                        .and_then(|digits| u32::from_str_radix(digits, 16).ok())
+                        .and_then(char::from_u32)
+                        .ok_or_else(|| {
+                            Error::Message(format!("invalid unicode escape at offset {}", escape))
+                        })?;
+                    rv.push(c);
+                    chars.nth(3);
+                }
+                Some((_, c)) => rv.push(c),
+                None => break,
+            },
+            c => rv.push(c),
+        }
+    }
+    Err(Error::UnterminatedQuote { offset: start })
+}
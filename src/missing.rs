@@ -0,0 +1,151 @@
+use std::cell::Cell;
+
+use serde::de::{self, Visitor};
+
+use crate::error::Error;
+
+/// Deserializes a value and fills in struct fields that were left out.
+///
+/// Once its input is exhausted, the map access of a struct passes every
+/// field of the `fields` serde hands to `deserialize_struct` that it was
+/// not given.  They read as `false`, as an empty sequence or as `None`,
+/// so left out fields are resolved in the same run.  Fields of other
+/// types, which are either required or have a default, and aliases of
+/// fields that are already set cannot be filled in.  If one of them
+/// makes `f` fail, it is skipped for structs with the same fields and `f`
+/// runs again, so that serde reports it as missing or uses its default.
+/// Runs are only repeated for such fields and not for the input.
+pub(crate) fn resolve_left_out<T, F>(mut f: F) -> Result<T, Error>
+where
+    F: FnMut(&Skipped) -> Result<T, Error>,
+{
+    let mut skipped = Skipped::default();
+    loop {
+        match f(&skipped) {
+            Err(error) => match skipped.pending.take() {
+                Some(field) => skipped.fields.push(field),
+                None => return Err(error),
+            },
+            rv => return rv,
+        }
+    }
+}
+
+/// A field of a struct, identified by the fields of the struct.
+type Field = (&'static [&'static str], &'static str);
+
+/// The left out fields that cannot be filled in.
+#[derive(Default)]
+pub(crate) struct Skipped {
+    fields: Vec<Field>,
+    /// The field that is being filled in.
+    pending: Cell<Option<Field>>,
+}
+
+impl Skipped {
+    fn contains(&self, fields: &[&str], field: &str) -> bool {
+        self.fields
+            .iter()
+            .any(|&(other_fields, other)| other == field && other_fields == fields)
+    }
+}
+
+/// A value from the input or a field that was left out.
+pub(crate) enum Entry<T> {
+    Given(T),
+    LeftOut(&'static str),
+}
+
+/// Tracks the fields a map access has yet to fill in.
+pub(crate) struct LeftOutFields<'a> {
+    struct_fields: &'static [&'static str],
+    /// The fields to fill in, in reverse order.
+    fields: Vec<&'static str>,
+    skipped: &'a Skipped,
+}
+
+impl<'a> LeftOutFields<'a> {
+    /// Fills in the fields of a struct that are not skipped.  Maps have no
+    /// fields to fill in.
+    pub(crate) fn new(
+        fields: Option<&'static [&'static str]>,
+        skipped: &'a Skipped,
+    ) -> LeftOutFields<'a> {
+        let struct_fields = fields.unwrap_or(&[]);
+        LeftOutFields {
+            struct_fields,
+            fields: struct_fields
+                .iter()
+                .rev()
+                .copied()
+                .filter(|field| !skipped.contains(struct_fields, field))
+                .collect(),
+            skipped,
+        }
+    }
+
+    /// Records a key that was given in the input.
+    pub(crate) fn given(&mut self, key: &str) {
+        self.fields.retain(|field| *field != key);
+    }
+
+    /// Returns the next field to fill in once the input is exhausted.
+    pub(crate) fn next(&mut self) -> Option<&'static str> {
+        let field = self.fields.pop()?;
+        self.skipped.pending.set(Some((self.struct_fields, field)));
+        Some(field)
+    }
+
+    /// Records that the last field was filled in.
+    pub(crate) fn filled(&self) {
+        self.skipped.pending.set(None);
+    }
+
+    /// Returns the number of fields left to fill in.
+    pub(crate) fn len(&self) -> usize {
+        self.fields.len()
+    }
+}
+
+/// Deserializes a field that was left out.
+pub(crate) struct MissingDeserializer {
+    pub(crate) field: &'static str,
+}
+
+impl<'de> de::Deserializer<'de> for MissingDeserializer {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, _visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        Err(Error::MissingField(self.field))
+    }
+
+    fn deserialize_bool<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_bool(false)
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_none()
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_seq(de::value::SeqDeserializer::new(std::iter::empty::<()>()))
+    }
+
+    serde::forward_to_deserialize_any! {
+        i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct newtype_struct tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
@@ -0,0 +1,886 @@
+//! Encoding parameters in the styles of OpenAPI.
+//!
+//! OpenAPI describes how path, query and header parameters are encoded
+//! with a `style` and an `explode` flag.  A [`Parameter`] serializes and
+//! deserializes primitives, sequences and flat structs or maps in each of
+//! the styles:
+//!
+//! | style    | explode | primitive     | array                         | object               |
+//! |----------|---------|---------------|-------------------------------|----------------------|
+//! | `simple` | no      | `blue`        | `blue,black`                  | `R,100,G,200`        |
+//! | `simple` | yes     | `blue`        | `blue,black`                  | `R=100,G=200`        |
+//! | `label`  | no      | `.blue`       | `.blue.black`                 | `.R.100.G.200`       |
+//! | `label`  | yes     | `.blue`       | `.blue.black`                 | `.R=100.G=200`       |
+//! | `matrix` | no      | `;color=blue` | `;color=blue,black`           | `;color=R,100,G,200` |
+//! | `matrix` | yes     | `;color=blue` | `;color=blue;color=black`     | `;R=100;G=200`       |
+//! | `form`   | no      | `color=blue`  | `color=blue,black`            | `color=R,100,G,200`  |
+//! | `form`   | yes     | `color=blue`  | `color=blue&color=black`      | `R=100&G=200`        |
+//!
+//! ```rust
+//! use serde_plain::openapi::{Parameter, Style};
+//!
+//! let param = Parameter::new("id").style(Style::Form).explode(true);
+//! assert_eq!(param.to_string(&vec![1, 2]).unwrap(), "id=1&id=2");
+//! assert_eq!(param.from_str::<Vec<u32>>("id=1&id=2").unwrap(), vec![1, 2]);
+//! ```
+//!
+//! Empty values, `None` and empty sequences are written as `;color` in
+//! matrix style, `.` in label style, `color=` in form style and as an empty
+//! string in simple style.  An array with a single empty item would read
+//! back as an empty array and fails to serialize, and objects with empty
+//! or repeated keys fail to parse.  Every value is parsed and
+//! formatted like [`from_str`](crate::from_str) and
+//! [`to_string`](crate::to_string) do.  Values are not percent-encoded, so
+//! array items and object keys and values that contain the delimiters of
+//! a style (`,`, `.`, `;`, `&` or `=`) fail to serialize instead of being
+//! read back as several values.
+use std::collections::HashSet;
+use std::ops::Range;
+
+use serde::de::{self, DeserializeOwned, Visitor};
+use serde::ser;
+
+use crate::config::Config;
+use crate::de::{ConfiguredDeserializer, Deserializer};
+use crate::error::Error;
+use crate::flat::{dotted_key, wrap_position, FlatSerializer, Scheme};
+use crate::ser::{ConfiguredSerializer, Serializer};
+
+/// The style of a parameter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Style {
+    /// Comma-separated values (`blue,black`), the default for path and
+    /// header parameters.
+    #[default]
+    Simple,
+    /// Values prefixed with a dot (`.blue.black`).
+    Label,
+    /// Path-style parameters (`;color=blue,black`).
+    Matrix,
+    /// Query-style parameters (`color=blue,black`), the default for query
+    /// and cookie parameters.
+    Form,
+}
+
+/// A named parameter with its style.
+#[derive(Debug, Clone)]
+pub struct Parameter {
+    name: String,
+    style: Style,
+    explode: bool,
+}
+
+impl Parameter {
+    /// Creates a parameter in the `simple` style without explode.
+    pub fn new(name: &str) -> Parameter {
+        Parameter {
+            name: name.to_string(),
+            style: Style::default(),
+            explode: false,
+        }
+    }
+
+    /// Sets the style of the parameter.
+    pub fn style(mut self, style: Style) -> Parameter {
+        self.style = style;
+        self
+    }
+
+    /// Writes array items and object properties as separate parameters.
+    pub fn explode(mut self, explode: bool) -> Parameter {
+        self.explode = explode;
+        self
+    }
+
+    /// Serialize a value in the style of the parameter.
+    pub fn to_string<T: ser::Serialize>(&self, value: &T) -> Result<String, Error> {
+        self.to_string_with_config(value, &Config::default())
+    }
+
+    /// Like [`to_string`](Parameter::to_string) but with a custom
+    /// configuration.
+    pub fn to_string_with_config<T: ser::Serialize>(
+        &self,
+        value: &T,
+        config: &Config,
+    ) -> Result<String, Error> {
+        let shape = value.serialize(ShapeSerializer { config })?;
+        self.render(shape)
+    }
+
+    /// Deserialize a value in the style of the parameter.
+    pub fn from_str<T: DeserializeOwned>(&self, s: &str) -> Result<T, Error> {
+        self.from_str_with_config(s, &Config::default())
+    }
+
+    /// Like [`from_str`](Parameter::from_str) but with a custom
+    /// configuration.
+    pub fn from_str_with_config<T: DeserializeOwned>(
+        &self,
+        s: &str,
+        config: &Config,
+    ) -> Result<T, Error> {
+        T::deserialize(ParameterDeserializer {
+            input: s,
+            param: self,
+            config,
+        })
+    }
+
+    /// Returns the prefix and separator of lists.
+    fn list_syntax(&self) -> (String, char) {
+        match self.style {
+            Style::Simple => (String::new(), ','),
+            Style::Label => (".".into(), '.'),
+            Style::Matrix => (format!(";{}=", self.name), ','),
+            Style::Form => (format!("{}=", self.name), ','),
+        }
+    }
+
+    fn render(&self, shape: Shape) -> Result<String, Error> {
+        let (prefix, separator) = self.list_syntax();
+        let items = match shape {
+            Shape::Items(ref items) if items.is_empty() => Vec::new(),
+            Shape::Pairs(ref pairs) if pairs.is_empty() => Vec::new(),
+            Shape::Primitive(value) => vec![value],
+            Shape::Items(ref items) if items.len() == 1 && items[0].is_empty() => {
+                return Err(Error::Message(
+                    "cannot represent a sequence with a single empty element".into(),
+                ))
+            }
+            Shape::Items(items) => {
+                if self.explode && self.style == Style::Matrix {
+                    return items
+                        .iter()
+                        .map(|item| {
+                            check_delimiters(item, &[';'])?;
+                            Ok(format!(";{}={}", self.name, item))
+                        })
+                        .collect();
+                } else if self.explode && self.style == Style::Form {
+                    return Ok(items
+                        .iter()
+                        .map(|item| {
+                            check_delimiters(item, &['&'])?;
+                            Ok(format!("{}={}", self.name, item))
+                        })
+                        .collect::<Result<Vec<_>, Error>>()?
+                        .join("&"));
+                }
+                for item in &items {
+                    check_delimiters(item, &[separator])?;
+                }
+                items
+            }
+            Shape::Pairs(pairs) => {
+                if pairs.iter().any(|(key, _)| key.is_empty()) {
+                    return Err(Error::Message("cannot represent an empty key".into()));
+                }
+                if self.explode {
+                    let (prefix, separator) = match self.style {
+                        Style::Simple => ("", ','),
+                        Style::Label => (".", '.'),
+                        Style::Matrix => (";", ';'),
+                        Style::Form => ("", '&'),
+                    };
+                    let pairs = pairs
+                        .iter()
+                        .map(|(key, value)| {
+                            // the value ends at the separator and the key at
+                            // the first `=`.
+                            check_delimiters(key, &[separator, '='])?;
+                            check_delimiters(value, &[separator])?;
+                            Ok(format!("{}={}", key, value))
+                        })
+                        .collect::<Result<Vec<_>, Error>>()?;
+                    return Ok(format!("{}{}", prefix, pairs.join(&separator.to_string())));
+                }
+                for (key, value) in &pairs {
+                    check_delimiters(key, &[separator])?;
+                    check_delimiters(value, &[separator])?;
+                }
+                pairs
+                    .into_iter()
+                    .flat_map(|(key, value)| vec![key, value])
+                    .collect()
+            }
+        };
+        Ok(match items[..] {
+            // an empty value is written without the `=` in matrix style.
+            [] if self.style == Style::Matrix => format!(";{}", self.name),
+            [ref item] if item.is_empty() && self.style == Style::Matrix => {
+                format!(";{}", self.name)
+            }
+            _ => format!("{}{}", prefix, items.join(&separator.to_string())),
+        })
+    }
+}
+
+/// Fails if a value contains one of the delimiters, which would split it
+/// when it is read back.
+fn check_delimiters(value: &str, delimiters: &[char]) -> Result<(), Error> {
+    match value.chars().find(|c| delimiters.contains(c)) {
+        Some(delimiter) => Err(Error::Message(format!(
+            "`{}` contains the delimiter `{}`",
+            value, delimiter
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// The structure of a serialized value.
+enum Shape {
+    Primitive(String),
+    Items(Vec<String>),
+    Pairs(Vec<(String, String)>),
+}
+
+/// The ranges of a key and its value in the input.
+type Pair = (Range<usize>, Range<usize>);
+
+/// Splits a range of the input at every separator.
+fn split(s: &str, range: Range<usize>, separator: char) -> Vec<Range<usize>> {
+    let mut rv = Vec::new();
+    let mut start = range.start;
+    for (idx, _) in s[range.clone()].match_indices(separator) {
+        rv.push(start..range.start + idx);
+        start = range.start + idx + 1;
+    }
+    rv.push(start..range.end);
+    rv
+}
+
+/// Splits a range into the key and the value at the first `=`.  A missing
+/// `=` yields an empty value.
+fn split_pair(s: &str, range: Range<usize>) -> (Range<usize>, Range<usize>) {
+    match s[range.clone()].find('=') {
+        Some(idx) => (
+            range.start..range.start + idx,
+            range.start + idx + 1..range.end,
+        ),
+        None => (range.clone(), range.end..range.end),
+    }
+}
+
+/// Deserializes a parameter.
+struct ParameterDeserializer<'de, 'a> {
+    input: &'de str,
+    param: &'a Parameter,
+    config: &'a Config,
+}
+
+impl<'de, 'a> ParameterDeserializer<'de, 'a> {
+    fn expected(&self, what: &str) -> Error {
+        Error::Message(format!(
+            "expected {} for parameter `{}`",
+            what, self.param.name
+        ))
+    }
+
+    /// Returns the range of the value or list of values.
+    fn payload(&self) -> Result<Range<usize>, Error> {
+        let s = self.input;
+        let name = &self.param.name;
+        match self.param.style {
+            Style::Simple => Ok(0..s.len()),
+            Style::Label => match s.strip_prefix('.') {
+                Some(_) => Ok(1..s.len()),
+                None => Err(self.expected("`.`")),
+            },
+            Style::Matrix => {
+                let rest = s
+                    .strip_prefix(';')
+                    .and_then(|rest| rest.strip_prefix(name.as_str()))
+                    .ok_or_else(|| self.expected(&format!("`;{}`", name)))?;
+                match rest.strip_prefix('=') {
+                    Some(_) => Ok(name.len() + 2..s.len()),
+                    None if rest.is_empty() => Ok(s.len()..s.len()),
+                    None => Err(self.expected(&format!("`;{}`", name))),
+                }
+            }
+            Style::Form => match s.strip_prefix(name.as_str()) {
+                Some(rest) if rest.starts_with('=') => Ok(name.len() + 1..s.len()),
+                _ => Err(self.expected(&format!("`{}=`", name))),
+            },
+        }
+    }
+
+    /// Returns the keys and values of exploded matrix and form parameters.
+    fn pieces(&self) -> Result<Vec<Pair>, Error> {
+        let s = self.input;
+        let range = match self.param.style {
+            Style::Matrix => match s.strip_prefix(';') {
+                Some(_) => 1..s.len(),
+                None => return Err(self.expected("`;`")),
+            },
+            _ => 0..s.len(),
+        };
+        let separator = if self.param.style == Style::Matrix {
+            ';'
+        } else {
+            '&'
+        };
+        let pieces: Vec<_> = split(s, range, separator)
+            .into_iter()
+            .map(|piece| split_pair(s, piece))
+            .collect();
+        // `;color` and `color=` are empty values.
+        match pieces[..] {
+            [(ref key, ref value)] if value.is_empty() && s[key.clone()] == *self.param.name => {
+                Ok(Vec::new())
+            }
+            _ => Ok(pieces),
+        }
+    }
+
+    fn is_exploded_pieces(&self) -> bool {
+        self.param.explode && matches!(self.param.style, Style::Matrix | Style::Form)
+    }
+
+    /// Returns the ranges of the items of an array.
+    fn items(&self) -> Result<Vec<Range<usize>>, Error> {
+        if self.is_exploded_pieces() {
+            return self
+                .pieces()?
+                .into_iter()
+                .map(|(key, value)| {
+                    if self.input[key] == self.param.name {
+                        Ok(value)
+                    } else {
+                        Err(self.expected(&format!("only `{}`", self.param.name)))
+                    }
+                })
+                .collect();
+        }
+        let payload = self.payload()?;
+        if payload.is_empty() {
+            return Ok(Vec::new());
+        }
+        Ok(split(self.input, payload, self.param.list_syntax().1))
+    }
+
+    /// Returns the ranges of the properties of an object.
+    fn pairs(&self) -> Result<Vec<Pair>, Error> {
+        let pairs = if self.is_exploded_pieces() {
+            self.pieces()?
+        } else {
+            let payload = self.payload()?;
+            if payload.is_empty() {
+                return Ok(Vec::new());
+            }
+            let items = split(self.input, payload, self.param.list_syntax().1);
+            if self.param.explode {
+                items
+                    .into_iter()
+                    .map(|item| split_pair(self.input, item))
+                    .collect()
+            } else if items.len() % 2 != 0 {
+                return Err(self.expected("pairs of keys and values"));
+            } else {
+                items
+                    .chunks(2)
+                    .map(|pair| (pair[0].clone(), pair[1].clone()))
+                    .collect()
+            }
+        };
+
+        // an empty or repeated key would silently replace a property.
+        let mut seen = HashSet::new();
+        for (key, _) in &pairs {
+            let name = &self.input[key.clone()];
+            if name.is_empty() {
+                return Err(self.expected("a name for every property"));
+            }
+            if !seen.insert(name) {
+                return Err(Error::Message(format!("duplicate property `{}`", name))
+                    .located(name, key.clone()));
+            }
+        }
+        Ok(pairs)
+    }
+
+    /// Parses the primitive value with `f`.
+    fn value<T, F>(self, f: F) -> Result<T, Error>
+    where
+        F: FnOnce(ConfiguredDeserializer<'de, 'a>) -> Result<T, Error>,
+    {
+        let range = self.payload()?;
+        f(Deserializer::with_config(
+            &self.input[range.clone()],
+            self.config,
+        ))
+        .map_err(|error| error.shifted(range.start))
+    }
+}
+
+macro_rules! forward_to_value {
+    ($($func:ident)*) => {
+        $(
+            fn $func<V>(self, visitor: V) -> Result<V::Value, Error>
+            where
+                V: Visitor<'de>,
+            {
+                self.value(|de| de::Deserializer::$func(de, visitor))
+            }
+        )*
+    };
+}
+
+impl<'de, 'a> de::Deserializer<'de> for ParameterDeserializer<'de, 'a> {
+    type Error = Error;
+
+    forward_to_value!(deserialize_any deserialize_bool deserialize_i8 deserialize_i16
+        deserialize_i32 deserialize_i64 deserialize_i128 deserialize_u8 deserialize_u16
+        deserialize_u32 deserialize_u64 deserialize_u128 deserialize_f32 deserialize_f64
+        deserialize_char deserialize_str deserialize_string deserialize_bytes
+        deserialize_byte_buf deserialize_unit deserialize_identifier
+        deserialize_ignored_any);
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        let is_empty = if self.is_exploded_pieces() {
+            self.pieces()?.is_empty()
+        } else {
+            self.payload()?.is_empty()
+        };
+        if is_empty {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_unit_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.value(|de| de::Deserializer::deserialize_unit_struct(de, name, visitor))
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        let items = self.items()?;
+        visitor.visit_seq(ItemAccess {
+            input: self.input,
+            items: items.into_iter().enumerate(),
+            config: self.config,
+        })
+    }
+
+    fn deserialize_tuple<V>(self, _len: usize, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        let pairs = self.pairs()?;
+        visitor.visit_map(PairAccess {
+            input: self.input,
+            pairs: pairs.into_iter(),
+            value: None,
+            config: self.config,
+        })
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.value(|de| de::Deserializer::deserialize_enum(de, name, variants, visitor))
+    }
+}
+
+/// Parses a value of an array or object.
+fn item<'de, 'a, T, F>(
+    input: &'de str,
+    range: Range<usize>,
+    config: &'a Config,
+    f: F,
+) -> Result<T, Error>
+where
+    F: FnOnce(ConfiguredDeserializer<'de, 'a>) -> Result<T, Error>,
+{
+    f(Deserializer::with_config(&input[range.clone()], config))
+        .map_err(|error| error.shifted(range.start))
+}
+
+/// Feeds the items of an array to a visitor.
+struct ItemAccess<'de, 'a> {
+    input: &'de str,
+    items: std::iter::Enumerate<std::vec::IntoIter<Range<usize>>>,
+    config: &'a Config,
+}
+
+impl<'de, 'a> de::SeqAccess<'de> for ItemAccess<'de, 'a> {
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Error>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        match self.items.next() {
+            Some((idx, range)) => item(self.input, range.clone(), self.config, |de| {
+                seed.deserialize(de)
+            })
+            .map(Some)
+            .map_err(|error| error.located(&format!("[{}]", idx), range)),
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.items.len())
+    }
+}
+
+/// Feeds the properties of an object to a visitor.
+struct PairAccess<'de, 'a> {
+    input: &'de str,
+    pairs: std::vec::IntoIter<Pair>,
+    value: Option<Pair>,
+    config: &'a Config,
+}
+
+impl<'de, 'a> de::MapAccess<'de> for PairAccess<'de, 'a> {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Error>
+    where
+        K: de::DeserializeSeed<'de>,
+    {
+        match self.pairs.next() {
+            Some((key, value)) => {
+                self.value = Some((key.clone(), value));
+                item(self.input, key, self.config, |de| seed.deserialize(de)).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Error>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        match self.value.take() {
+            Some((key, value)) => item(self.input, value.clone(), self.config, |de| {
+                seed.deserialize(de)
+            })
+            .map_err(|error| error.located(&self.input[key], value)),
+            None => Err(Error::Message("value requested before key".into())),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.pairs.len())
+    }
+}
+
+/// Properties of nested structs get dotted names.
+static PROPERTIES: Scheme = Scheme {
+    key: dotted_key,
+    wrap: wrap_position,
+    flatten_maps: false,
+};
+
+/// Serializes a value into its shape.
+struct ShapeSerializer<'a> {
+    config: &'a Config,
+}
+
+impl<'a> ShapeSerializer<'a> {
+    fn plain(&self) -> ConfiguredSerializer<'a> {
+        Serializer::with_config(self.config)
+    }
+}
+
+macro_rules! serialize_primitive {
+    ($($ty:ty => $meth:ident,)*) => {
+        $(fn $meth(self, v: $ty) -> Result<Shape, Error> {
+            ser::Serializer::$meth(self.plain(), v).map(Shape::Primitive)
+        })*
+    };
+}
+
+impl<'a> ser::Serializer for ShapeSerializer<'a> {
+    type Ok = Shape;
+    type Error = Error;
+    type SerializeSeq = ItemsSerializer<'a>;
+    type SerializeTuple = ItemsSerializer<'a>;
+    type SerializeTupleStruct = ItemsSerializer<'a>;
+    type SerializeTupleVariant = ser::Impossible<Shape, Error>;
+    type SerializeMap = PairsSerializer<'a>;
+    type SerializeStruct = PairsSerializer<'a>;
+    type SerializeStructVariant = ser::Impossible<Shape, Error>;
+
+    serialize_primitive! {
+        bool => serialize_bool,
+        u8  => serialize_u8,
+        u16 => serialize_u16,
+        u32 => serialize_u32,
+        u64 => serialize_u64,
+        u128 => serialize_u128,
+        i8  => serialize_i8,
+        i16 => serialize_i16,
+        i32 => serialize_i32,
+        i64 => serialize_i64,
+        i128 => serialize_i128,
+        f32 => serialize_f32,
+        f64 => serialize_f64,
+        char => serialize_char,
+        &str => serialize_str,
+        &[u8] => serialize_bytes,
+    }
+
+    fn serialize_unit(self) -> Result<Shape, Error> {
+        ser::Serializer::serialize_unit(self.plain()).map(Shape::Primitive)
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<Shape, Error> {
+        ser::Serializer::serialize_unit_struct(self.plain(), name).map(Shape::Primitive)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Shape, Error> {
+        ser::Serializer::serialize_unit_variant(self.plain(), name, variant_index, variant)
+            .map(Shape::Primitive)
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + ser::Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Shape, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + ser::Serialize>(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Shape, Error> {
+        ser::Serializer::serialize_newtype_variant(
+            self.plain(),
+            name,
+            variant_index,
+            variant,
+            value,
+        )
+        .map(Shape::Primitive)
+    }
+
+    fn serialize_none(self) -> Result<Shape, Error> {
+        Ok(Shape::Items(Vec::new()))
+    }
+
+    fn serialize_some<T: ?Sized + ser::Serialize>(self, value: &T) -> Result<Shape, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Error> {
+        Ok(ItemsSerializer {
+            config: self.config,
+            items: Vec::with_capacity(len.unwrap_or(0)),
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Error> {
+        Err(Error::ImpossibleSerialization("tuple variant"))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Error> {
+        Ok(PairsSerializer {
+            config: self.config,
+            pairs: Vec::new(),
+            key: None,
+        })
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, Error> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Error> {
+        Err(Error::ImpossibleSerialization("struct variant"))
+    }
+}
+
+/// Serializes the items of an array.
+struct ItemsSerializer<'a> {
+    config: &'a Config,
+    items: Vec<String>,
+}
+
+impl<'a> ser::SerializeSeq for ItemsSerializer<'a> {
+    type Ok = Shape;
+    type Error = Error;
+
+    fn serialize_element<T: ?Sized + ser::Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        let item = value.serialize(Serializer::with_config(self.config))?;
+        self.items.push(item);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Shape, Error> {
+        Ok(Shape::Items(self.items))
+    }
+}
+
+impl<'a> ser::SerializeTuple for ItemsSerializer<'a> {
+    type Ok = Shape;
+    type Error = Error;
+
+    fn serialize_element<T: ?Sized + ser::Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Shape, Error> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+impl<'a> ser::SerializeTupleStruct for ItemsSerializer<'a> {
+    type Ok = Shape;
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + ser::Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Shape, Error> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+/// Serializes the properties of an object.
+struct PairsSerializer<'a> {
+    config: &'a Config,
+    pairs: Vec<(String, String)>,
+    key: Option<String>,
+}
+
+impl<'a> ser::SerializeMap for PairsSerializer<'a> {
+    type Ok = Shape;
+    type Error = Error;
+
+    fn serialize_key<T: ?Sized + ser::Serialize>(&mut self, key: &T) -> Result<(), Error> {
+        self.key = Some(key.serialize(Serializer::with_config(self.config))?);
+        Ok(())
+    }
+
+    fn serialize_value<T: ?Sized + ser::Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        let key = self
+            .key
+            .take()
+            .ok_or_else(|| Error::Message("value serialized before key".into()))?;
+        value.serialize(FlatSerializer::new(
+            &mut self.pairs,
+            &key,
+            self.config,
+            &PROPERTIES,
+        ))
+    }
+
+    fn end(self) -> Result<Shape, Error> {
+        Ok(Shape::Pairs(self.pairs))
+    }
+}
+
+impl<'a> ser::SerializeStruct for PairsSerializer<'a> {
+    type Ok = Shape;
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + ser::Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        ser::SerializeMap::serialize_entry(self, key, value)
+    }
+
+    fn end(self) -> Result<Shape, Error> {
+        ser::SerializeMap::end(self)
+    }
+}
@@ -0,0 +1,169 @@
+use std::ops::Range;
+
+use crate::config::Config;
+use crate::error::Error;
+use crate::quote;
+
+/// A piece of a parsed pattern.
+#[derive(Debug, Clone)]
+enum Piece {
+    Literal(String),
+    Field(String),
+}
+
+/// A parsed format pattern like `{major}.{minor}.{patch}`.
+#[derive(Debug, Clone)]
+pub(crate) struct Pattern {
+    pieces: Vec<Piece>,
+}
+
+impl Pattern {
+    /// Parses a pattern.  Braces are escaped by doubling them (`{{`).
+    pub(crate) fn parse(template: &str) -> Result<Pattern, Error> {
+        let invalid =
+            |msg: &str| Error::Message(format!("invalid pattern `{}`: {}", template, msg));
+        let mut pieces = Vec::new();
+        let mut literal = String::new();
+        let mut chars = template.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '{' => {
+                    let mut name = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => name.push(c),
+                            None => return Err(invalid("unclosed placeholder")),
+                        }
+                    }
+                    if name.is_empty() {
+                        return Err(invalid("empty placeholder"));
+                    }
+                    if !literal.is_empty() {
+                        pieces.push(Piece::Literal(std::mem::take(&mut literal)));
+                    } else if let Some(Piece::Field(_)) = pieces.last() {
+                        return Err(invalid("placeholders must be separated by text"));
+                    }
+                    pieces.push(Piece::Field(name));
+                }
+                '}' => return Err(invalid("unmatched `}`")),
+                c => literal.push(c),
+            }
+        }
+        if !literal.is_empty() {
+            pieces.push(Piece::Literal(literal));
+        }
+        Ok(Pattern { pieces })
+    }
+
+    /// Returns the names of the placeholders in order.
+    pub(crate) fn fields(&self) -> impl Iterator<Item = &str> {
+        self.pieces.iter().filter_map(|piece| match *piece {
+            Piece::Field(ref name) => Some(name.as_str()),
+            Piece::Literal(_) => None,
+        })
+    }
+
+    /// Fills the placeholders with the given values.
+    pub(crate) fn render(
+        &self,
+        values: &[(String, String)],
+        config: &Config,
+    ) -> Result<String, Error> {
+        for (name, _) in values {
+            if !self.fields().any(|field| field == name) {
+                return Err(Error::Message(format!(
+                    "field `{}` is not part of the pattern",
+                    name
+                )));
+            }
+        }
+
+        let mut rv = String::new();
+        for (idx, piece) in self.pieces.iter().enumerate() {
+            match *piece {
+                Piece::Literal(ref literal) => rv.push_str(literal),
+                Piece::Field(ref name) => {
+                    let value = values
+                        .iter()
+                        .find(|(field, _)| field == name)
+                        .map(|(_, value)| value)
+                        .ok_or_else(|| {
+                            Error::Message(format!("no value for placeholder `{{{}}}`", name))
+                        })?;
+                    if let Some(Piece::Literal(ref next)) = self.pieces.get(idx + 1) {
+                        if quote::find(value, next, config, 0)?.is_some() {
+                            return Err(Error::Message(format!(
+                                "value `{}` for placeholder `{{{}}}` contains `{}`",
+                                value, name, next
+                            )));
+                        }
+                    }
+                    rv.push_str(value);
+                }
+            }
+        }
+        Ok(rv)
+    }
+
+    /// Splits the input into the ranges of the placeholders.
+    ///
+    /// Each placeholder extends up to the first occurrence of the text that
+    /// follows it in the pattern.
+    pub(crate) fn split<'p>(
+        &'p self,
+        input: &str,
+        config: &Config,
+        base: usize,
+    ) -> Result<Vec<(&'p str, Range<usize>)>, Error> {
+        let mut rv = Vec::new();
+        let mut pos = 0;
+        for (idx, piece) in self.pieces.iter().enumerate() {
+            match *piece {
+                Piece::Literal(ref literal) => {
+                    if !input[pos..].starts_with(literal.as_str()) {
+                        return Err(Error::Message(format!(
+                            "expected `{}` at offset {}",
+                            literal,
+                            base + pos
+                        )));
+                    }
+                    pos += literal.len();
+                }
+                Piece::Field(ref name) => {
+                    let end = match self.pieces.get(idx + 1) {
+                        Some(Piece::Literal(ref next)) => {
+                            match quote::find(&input[pos..], next, config, base + pos)? {
+                                Some(end) => pos + end,
+                                None => {
+                                    return Err(Error::Message(format!(
+                                        "expected `{}` after placeholder `{{{}}}`",
+                                        next, name
+                                    )))
+                                }
+                            }
+                        }
+                        _ => input.len(),
+                    };
+                    rv.push((name.as_str(), pos..end));
+                    pos = end;
+                }
+            }
+        }
+        if pos != input.len() {
+            return Err(Error::Message(format!(
+                "unexpected trailing input at offset {}",
+                base + pos
+            )));
+        }
+        Ok(rv)
+    }
+}
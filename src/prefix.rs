@@ -0,0 +1,370 @@
+use std::borrow::Cow;
+
+use serde::de::{self, Deserialize, Visitor};
+
+use crate::config::{Config, ParseMode};
+use crate::de::ConfiguredDeserializer;
+use crate::error::Error;
+use crate::quote;
+
+/// Deserialize an instance of type `T` from the start of a string and
+/// return it together with the rest of the input.
+///
+/// Numbers, booleans, chars and unit variants consume the longest prefix
+/// that forms a valid token.  Everything else, including strings, consumes
+/// the entire input.  Use [`from_str_until`] to stop at a terminator
+/// instead.  In [`ParseMode::Trimmed`] leading whitespace is skipped before
+/// a token; whitespace after it stays in the rest.
+///
+/// ```rust
+/// # #[macro_use] extern crate serde_derive;
+/// # fn main() {
+/// #[derive(Deserialize, Debug, PartialEq)]
+/// #[serde(rename_all = "lowercase")]
+/// pub enum Unit {
+///     Ms,
+///     S,
+/// }
+///
+/// let (value, rest) = serde_plain::from_str_prefix::<u64>("250ms").unwrap();
+/// assert_eq!((value, rest), (250, "ms"));
+/// let (unit, rest) = serde_plain::from_str_prefix::<Unit>(rest).unwrap();
+/// assert_eq!((unit, rest), (Unit::Ms, ""));
+/// # }
+/// ```
+pub fn from_str_prefix<'a, T>(s: &'a str) -> Result<(T, &'a str), Error>
+where
+    T: Deserialize<'a>,
+{
+    from_str_prefix_with_config(s, &Config::default())
+}
+
+/// Like [`from_str_prefix`] but with a custom configuration.
+pub fn from_str_prefix_with_config<'a, T>(
+    s: &'a str,
+    config: &Config,
+) -> Result<(T, &'a str), Error>
+where
+    T: Deserialize<'a>,
+{
+    deserialize_prefix(s, None, config)
+}
+
+/// Deserialize an instance of type `T` from a string up to the first
+/// occurrence of `terminator` and return it together with the rest of the
+/// input.
+///
+/// The rest starts with the terminator.  If the terminator does not occur
+/// the entire input is consumed.  With [`Quoting::DoubleQuotes`] a
+/// terminator within quotes is skipped.
+///
+/// ```rust
+/// let (key, rest) = serde_plain::from_str_until::<String>("name: value", ":").unwrap();
+/// assert_eq!((key.as_str(), rest), ("name", ": value"));
+/// ```
+///
+/// [`Quoting::DoubleQuotes`]: crate::Quoting::DoubleQuotes
+pub fn from_str_until<'a, T>(s: &'a str, terminator: &str) -> Result<(T, &'a str), Error>
+where
+    T: Deserialize<'a>,
+{
+    from_str_until_with_config(s, terminator, &Config::default())
+}
+
+/// Like [`from_str_until`] but with a custom configuration.
+pub fn from_str_until_with_config<'a, T>(
+    s: &'a str,
+    terminator: &str,
+    config: &Config,
+) -> Result<(T, &'a str), Error>
+where
+    T: Deserialize<'a>,
+{
+    deserialize_prefix(s, Some(terminator), config)
+}
+
+fn deserialize_prefix<'a, T>(
+    s: &'a str,
+    terminator: Option<&str>,
+    config: &Config,
+) -> Result<(T, &'a str), Error>
+where
+    T: Deserialize<'a>,
+{
+    let input = match terminator {
+        Some(terminator) => match quote::find(s, terminator, config, 0)? {
+            Some(end) => &s[..end],
+            None => s,
+        },
+        None => s,
+    };
+    let mut consumed = 0;
+    let value = T::deserialize(PrefixDeserializer {
+        input,
+        config,
+        scan: terminator.is_none(),
+        consumed: &mut consumed,
+    })?;
+    Ok((value, &s[consumed..]))
+}
+
+/// Deserializes a value from the start of the input and records how much of
+/// it was consumed.
+struct PrefixDeserializer<'de, 'a> {
+    input: &'de str,
+    config: &'a Config,
+    scan: bool,
+    consumed: &'a mut usize,
+}
+
+impl<'de, 'a> PrefixDeserializer<'de, 'a> {
+    /// Consumes the first `len` bytes and returns a deserializer for them.
+    fn take(self, len: usize) -> ConfiguredDeserializer<'de, 'a> {
+        *self.consumed = len;
+        ConfiguredDeserializer::nested(Cow::Borrowed(&self.input[..len]), 0, self.config)
+    }
+
+    /// Returns the length of the leading whitespace that is skipped before
+    /// a token in [`ParseMode::Trimmed`].
+    fn leading(&self) -> usize {
+        match self.config.parse_mode {
+            ParseMode::Trimmed => self.input.len() - self.input.trim_start().len(),
+            ParseMode::Exact | ParseMode::Canonical => 0,
+        }
+    }
+
+    /// Consumes the token that `scan` finds or the entire input.
+    fn token(self, scan: fn(&str, &Config) -> usize) -> ConfiguredDeserializer<'de, 'a> {
+        let len = if self.scan {
+            let start = self.leading();
+            start + scan(&self.input[start..], self.config)
+        } else {
+            self.input.len()
+        };
+        self.take(len)
+    }
+
+    fn rest(self) -> ConfiguredDeserializer<'de, 'a> {
+        let len = self.input.len();
+        self.take(len)
+    }
+}
+
+/// Returns the length of the leading ASCII digits.
+fn digits(input: &str) -> usize {
+    input.bytes().take_while(u8::is_ascii_digit).count()
+}
+
+/// Returns the length of an optional sign.
+fn sign(input: &str, signs: &[u8]) -> usize {
+    match input.as_bytes().first() {
+        Some(c) if signs.contains(c) => 1,
+        _ => 0,
+    }
+}
+
+fn scan_unsigned(input: &str, _config: &Config) -> usize {
+    let start = sign(input, b"+");
+    start + digits(&input[start..])
+}
+
+fn scan_signed(input: &str, _config: &Config) -> usize {
+    let start = sign(input, b"+-");
+    start + digits(&input[start..])
+}
+
+fn scan_float(input: &str, config: &Config) -> usize {
+    let reprs = [
+        config.nan_repr.as_deref(),
+        config.infinity_repr.as_deref(),
+        config.neg_infinity_repr.as_deref(),
+    ];
+    let named = reprs
+        .iter()
+        .flatten()
+        .filter(|repr| !repr.is_empty() && input.starts_with(**repr))
+        .map(|repr| repr.len())
+        .max();
+    if let Some(len) = named {
+        return len;
+    }
+
+    // the special values `f64::from_str` understands, in any case.
+    let mut len = sign(input, b"+-");
+    let special = ["infinity", "inf", "nan"].iter().find(|name| {
+        input
+            .get(len..len + name.len())
+            .is_some_and(|word| word.eq_ignore_ascii_case(name))
+    });
+    if let Some(name) = special {
+        return len + name.len();
+    }
+    len += digits(&input[len..]);
+    if input[len..].starts_with('.') {
+        len += 1 + digits(&input[len + 1..]);
+    }
+    let rest = &input[len..];
+    if rest.starts_with(['e', 'E']) {
+        let exp_sign = sign(&rest[1..], b"+-");
+        let exp_digits = digits(&rest[1 + exp_sign..]);
+        if exp_digits > 0 {
+            len += 1 + exp_sign + exp_digits;
+        }
+    }
+    len
+}
+
+fn scan_bool(input: &str, _config: &Config) -> usize {
+    ["true", "false"]
+        .iter()
+        .find(|value| input.starts_with(**value))
+        .map_or(0, |value| value.len())
+}
+
+fn scan_char(input: &str, _config: &Config) -> usize {
+    input.chars().next().map_or(0, char::len_utf8)
+}
+
+/// Returns the length of the longest variant the input starts with.
+///
+/// If no variant matches, a numeric index or the leading word is taken so
+/// that the error names what was found.
+fn scan_variant(input: &str, variants: &[&str], config: &Config) -> usize {
+    let index = if config.accept_variant_index {
+        digits(input)
+    } else {
+        0
+    };
+    variants
+        .iter()
+        .filter(|variant| !variant.is_empty() && input.starts_with(**variant))
+        .map(|variant| variant.len())
+        .chain(Some(index))
+        .max()
+        .filter(|len| *len > 0)
+        .unwrap_or_else(|| {
+            input
+                .find(|c: char| !c.is_alphanumeric() && c != '_' && c != '-')
+                .unwrap_or(input.len())
+        })
+}
+
+macro_rules! forward_to_token {
+    ($scan:ident => $($func:ident)*) => {
+        $(
+            fn $func<V>(self, visitor: V) -> Result<V::Value, Error>
+            where
+                V: Visitor<'de>,
+            {
+                self.token($scan).$func(visitor)
+            }
+        )*
+    };
+}
+
+macro_rules! forward_to_rest {
+    ($($func:ident)*) => {
+        $(
+            fn $func<V>(self, visitor: V) -> Result<V::Value, Error>
+            where
+                V: Visitor<'de>,
+            {
+                self.rest().$func(visitor)
+            }
+        )*
+    };
+}
+
+impl<'de, 'a> de::Deserializer<'de> for PrefixDeserializer<'de, 'a> {
+    type Error = Error;
+
+    forward_to_token!(scan_bool => deserialize_bool);
+    forward_to_token!(scan_signed => deserialize_i8 deserialize_i16 deserialize_i32
+        deserialize_i64 deserialize_i128);
+    forward_to_token!(scan_unsigned => deserialize_u8 deserialize_u16 deserialize_u32
+        deserialize_u64 deserialize_u128);
+    forward_to_token!(scan_float => deserialize_f32 deserialize_f64);
+    forward_to_token!(scan_char => deserialize_char);
+    forward_to_rest!(deserialize_any deserialize_str deserialize_string deserialize_bytes
+        deserialize_byte_buf deserialize_unit deserialize_seq deserialize_map
+        deserialize_identifier deserialize_ignored_any);
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        let none = self.config.none_str();
+        let input = &self.input[self.leading()..];
+        if input.is_empty() || (!none.is_empty() && input == none) {
+            return self.rest().deserialize_option(visitor);
+        }
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_unit_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.rest().deserialize_unit_struct(name, visitor)
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_tuple<V>(self, len: usize, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.rest().deserialize_tuple(len, visitor)
+    }
+
+    fn deserialize_tuple_struct<V>(
+        self,
+        name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.rest().deserialize_tuple_struct(name, len, visitor)
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.rest().deserialize_struct(name, fields, visitor)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        let len = if self.scan {
+            let start = self.leading();
+            start + scan_variant(&self.input[start..], variants, self.config)
+        } else {
+            self.input.len()
+        };
+        self.take(len).deserialize_enum(name, variants, visitor)
+    }
+}
@@ -0,0 +1,302 @@
+//! Reading and writing Java-style `.properties` files.
+//!
+//! A properties file consists of `key = value` lines.  Lines starting with
+//! `#` or `!` are comments, a backslash at the end of a line continues the
+//! value on the next line and the usual escape sequences (`\t`, `\n`,
+//! `\uXXXX`, ...) are supported.  Dotted keys such as `db.port` map to the
+//! fields of nested structs:
+//!
+//! ```rust
+//! # #[macro_use] extern crate serde_derive;
+//! # fn main() {
+//! #[derive(Deserialize, Serialize, Debug, PartialEq)]
+//! pub struct Database {
+//!     host: String,
+//!     port: u16,
+//! }
+//!
+//! #[derive(Deserialize, Serialize, Debug, PartialEq)]
+//! pub struct Settings {
+//!     name: String,
+//!     db: Database,
+//! }
+//!
+//! let input = "\
+//! # the service
+//! name = my service
+//! db.host = localhost
+//! db.port = 5432
+//! ";
+//! let settings: Settings = serde_plain::properties::from_str(input).unwrap();
+//! assert_eq!(settings.db.port, 5432);
+//! assert_eq!(
+//!     serde_plain::properties::to_string(&settings).unwrap(),
+//!     "name=my service\ndb.host=localhost\ndb.port=5432\n"
+//! );
+//! # }
+//! ```
+//!
+//! Every value is parsed and formatted like [`from_str`](crate::from_str)
+//! and [`to_string`](crate::to_string) do, so enums are written by name and
+//! numbers go through `FromStr`.  Maps with plain values are supported as
+//! well, their keys are the keys of the file.  Errors for values carry the
+//! key, line and column (`Error::Position`).
+use std::collections::BTreeMap;
+
+use serde::de::DeserializeOwned;
+use serde::ser;
+
+use crate::config::Config;
+use crate::error::Error;
+use crate::flat::{
+    dotted_key, wrap_position, Entry, FlatDeserializer, FlatSerializer, Position, Scheme,
+};
+
+/// Deserialize a struct or map from a properties file.
+pub fn from_str<T: DeserializeOwned>(s: &str) -> Result<T, Error> {
+    from_str_with_config(s, &Config::default())
+}
+
+/// Like [`from_str`] but with a custom configuration.
+pub fn from_str_with_config<T: DeserializeOwned>(s: &str, config: &Config) -> Result<T, Error> {
+    let entries = parse(s)?;
+    T::deserialize(FlatDeserializer::new(&entries, "", config, &PROPERTIES))
+}
+
+/// Serialize a struct or map into a properties file.
+///
+/// Every value is written as a `key=value` line, fields of nested structs
+/// use dotted keys.  Fields that are `None` are left out.
+pub fn to_string<T: ser::Serialize>(value: &T) -> Result<String, Error> {
+    to_string_with_config(value, &Config::default())
+}
+
+/// Like [`to_string`] but with a custom configuration.
+pub fn to_string_with_config<T: ser::Serialize>(
+    value: &T,
+    config: &Config,
+) -> Result<String, Error> {
+    let mut pairs = Vec::new();
+    value.serialize(FlatSerializer::new(&mut pairs, "", config, &PROPERTIES))?;
+    let mut rv = String::new();
+    for (key, value) in pairs {
+        escape(&mut rv, &key, true);
+        rv.push('=');
+        escape(&mut rv, &value, false);
+        rv.push('\n');
+    }
+    Ok(rv)
+}
+
+/// Keys are joined with dots.
+static PROPERTIES: Scheme = Scheme {
+    key: dotted_key,
+    wrap: wrap_position,
+    flatten_maps: true,
+};
+
+fn is_whitespace(c: char) -> bool {
+    c == ' ' || c == '\t' || c == '\x0c'
+}
+
+/// Returns `true` if the line ends with an unescaped backslash.
+fn is_continued(line: &str) -> bool {
+    line.chars().rev().take_while(|&c| c == '\\').count() % 2 == 1
+}
+
+/// A part of a logical line, which starts at the given byte offset of the
+/// logical line and at the given position of the file.
+struct Segment {
+    start: usize,
+    line: usize,
+    column: usize,
+}
+
+/// A line with its continuation lines joined.
+struct LogicalLine {
+    text: String,
+    segments: Vec<Segment>,
+}
+
+impl LogicalLine {
+    /// Returns the position of a byte offset of the logical line.
+    fn position(&self, offset: usize) -> Position {
+        let segment = self
+            .segments
+            .iter()
+            .rev()
+            .find(|segment| segment.start <= offset)
+            .unwrap_or(&self.segments[0]);
+        Position::Text {
+            line: segment.line,
+            column: segment.column + self.text[segment.start..offset].chars().count(),
+        }
+    }
+}
+
+/// Splits the input into logical lines, skipping comments and blank lines.
+fn logical_lines(s: &str) -> Vec<LogicalLine> {
+    let mut physical = s
+        .split('\n')
+        .map(|line| line.strip_suffix('\r').unwrap_or(line))
+        .enumerate();
+    let mut rv = Vec::new();
+    while let Some((idx, line)) = physical.next() {
+        let trimmed = line.trim_start_matches(is_whitespace);
+        if trimmed.is_empty() || trimmed.starts_with('#') || trimmed.starts_with('!') {
+            continue;
+        }
+        let mut logical = LogicalLine {
+            text: String::new(),
+            segments: Vec::new(),
+        };
+        let mut current = (idx, line, trimmed);
+        loop {
+            let (idx, line, trimmed) = current;
+            logical.segments.push(Segment {
+                start: logical.text.len(),
+                line: idx + 1,
+                column: line[..line.len() - trimmed.len()].chars().count() + 1,
+            });
+            if !is_continued(trimmed) {
+                logical.text.push_str(trimmed);
+                break;
+            }
+            logical.text.push_str(&trimmed[..trimmed.len() - 1]);
+            match physical.next() {
+                Some((idx, line)) => {
+                    current = (idx, line, line.trim_start_matches(is_whitespace));
+                }
+                None => break,
+            }
+        }
+        rv.push(logical);
+    }
+    rv
+}
+
+/// Parses a properties file into its entries.  Later keys replace earlier
+/// ones.
+fn parse(s: &str) -> Result<BTreeMap<String, Entry>, Error> {
+    let mut entries = BTreeMap::new();
+    for line in logical_lines(s) {
+        let text = &line.text;
+        let mut key_end = text.len();
+        let mut escaped = false;
+        for (idx, c) in text.char_indices() {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '=' || c == ':' || is_whitespace(c) {
+                key_end = idx;
+                break;
+            }
+        }
+        let rest = text[key_end..].trim_start_matches(is_whitespace);
+        let rest = rest
+            .strip_prefix(|c| c == '=' || c == ':')
+            .unwrap_or(rest)
+            .trim_start_matches(is_whitespace);
+        let value_start = text.len() - rest.len();
+
+        let key = unescape(&text[..key_end])
+            .map_err(|(offset, error)| syntax_error("", line.position(offset), error))?;
+        let value = unescape(rest).map_err(|(offset, error)| {
+            syntax_error(&key, line.position(value_start + offset), error)
+        })?;
+        entries.insert(
+            key,
+            Entry {
+                value,
+                position: Some(line.position(value_start)),
+            },
+        );
+    }
+    Ok(entries)
+}
+
+fn syntax_error(key: &str, position: Position, error: Error) -> Error {
+    (PROPERTIES.wrap)(key, Some(position), error)
+}
+
+/// Resolves the escape sequences of a key or value.  Errors carry the
+/// byte offset of the invalid escape sequence.
+fn unescape(s: &str) -> Result<String, (usize, Error)> {
+    let mut rv = String::with_capacity(s.len());
+    let mut chars = s.char_indices();
+    while let Some((offset, c)) = chars.next() {
+        if c != '\\' {
+            rv.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some((_, 't')) => rv.push('\t'),
+            Some((_, 'n')) => rv.push('\n'),
+            Some((_, 'r')) => rv.push('\r'),
+            Some((_, 'f')) => rv.push('\x0c'),
+            Some((_, 'u')) => {
+                // characters outside of the BMP are written as surrogate
+                // pairs.
+                let (c, len) = match hex4(s, offset + 2) {
+                    Some(high @ 0xd800..=0xdbff) => {
+                        let low = s
+                            .get(offset + 6..)
+                            .filter(|rest| rest.starts_with("\\u"))
+                            .and_then(|_| hex4(s, offset + 8));
+                        match low {
+                            Some(low @ 0xdc00..=0xdfff) => {
+                                let code = 0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00);
+                                (char::from_u32(code), 10)
+                            }
+                            _ => (None, 4),
+                        }
+                    }
+                    Some(code) => (char::from_u32(code), 4),
+                    None => (None, 4),
+                };
+                let c = c.ok_or_else(|| {
+                    let escape = s[offset..].chars().take(len + 2).collect::<String>();
+                    (
+                        offset,
+                        Error::Message(format!("invalid unicode escape `{}`", escape)),
+                    )
+                })?;
+                rv.push(c);
+                chars.nth(len - 1);
+            }
+            Some((_, c)) => rv.push(c),
+            None => {}
+        }
+    }
+    Ok(rv)
+}
+
+/// Parses four hex digits at the given byte offset.
+fn hex4(s: &str, offset: usize) -> Option<u32> {
+    let digits = s.get(offset..offset + 4)?;
+    if !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    u32::from_str_radix(digits, 16).ok()
+}
+
+/// Writes a key or value with the characters escaped that would otherwise
+/// change its meaning.
+fn escape(out: &mut String, s: &str, is_key: bool) {
+    for (idx, c) in s.chars().enumerate() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\t' => out.push_str("\\t"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\x0c' => out.push_str("\\f"),
+            ' ' if is_key || idx == 0 => out.push_str("\\ "),
+            '=' | ':' | '#' | '!' if is_key => {
+                out.push('\\');
+                out.push(c);
+            }
+            c => out.push(c),
+        }
+    }
+}
@@ -0,0 +1,300 @@
+use std::borrow::Cow;
+use std::fmt::Write;
+use std::ops::Range;
+
+use crate::config::{Config, Quoting};
+use crate::error::Error;
+
+/// Returns the end of the quoted string starting at `start`.
+///
+/// The returned index points right after the closing quote.
+fn skip_quoted(input: &str, start: usize, base: usize) -> Result<usize, Error> {
+    let bytes = input.as_bytes();
+    let mut idx = start + 1;
+    while idx < bytes.len() {
+        match bytes[idx] {
+            b'\\' => idx += 2,
+            b'"' => return Ok(idx + 1),
+            _ => idx += 1,
+        }
+    }
+    Err(Error::UnterminatedQuote {
+        offset: base + start,
+    })
+}
+
+/// Returns the end of the single-quoted string starting at `start`.
+fn skip_single_quoted(input: &str, start: usize, base: usize) -> Result<usize, Error> {
+    match input[start + 1..].find('\'') {
+        Some(idx) => Ok(start + idx + 2),
+        None => Err(Error::UnterminatedQuote {
+            offset: base + start,
+        }),
+    }
+}
+
+/// Returns the end of the quoted string or escape sequence at `idx`, or
+/// `None` if there is none.
+fn skip(input: &str, idx: usize, config: &Config, base: usize) -> Result<Option<usize>, Error> {
+    match (config.quoting, input.as_bytes()[idx]) {
+        (Quoting::DoubleQuotes, b'"') | (Quoting::Shell, b'"') => {
+            skip_quoted(input, idx, base).map(Some)
+        }
+        (Quoting::Shell, b'\'') => skip_single_quoted(input, idx, base).map(Some),
+        (Quoting::Shell, b'\\') => Ok(Some(
+            input[idx + 1..]
+                .chars()
+                .next()
+                .map_or(input.len(), |c| idx + 1 + c.len_utf8()),
+        )),
+        _ => Ok(None),
+    }
+}
+
+/// Splits the input at every separator that is not within quotes.
+///
+/// `base` is the offset of the input in the original string and is used
+/// for error reporting.
+pub(crate) fn split(
+    input: &str,
+    separator: &str,
+    config: &Config,
+    base: usize,
+) -> Result<Vec<Range<usize>>, Error> {
+    if config.quoting == Quoting::Shell && is_whitespace(separator) {
+        return split_words(input, config, base);
+    }
+    let mut rv = Vec::new();
+    let mut start = 0;
+    while let Some(idx) = find(&input[start..], separator, config, base + start)? {
+        rv.push(start..start + idx);
+        start += idx + separator.len();
+    }
+    rv.push(start..input.len());
+    Ok(rv)
+}
+
+fn is_whitespace(separator: &str) -> bool {
+    !separator.is_empty() && separator.bytes().all(|b| b.is_ascii_whitespace())
+}
+
+/// Splits the input into shell words which are separated by runs of
+/// whitespace outside of quotes.
+fn split_words(input: &str, config: &Config, base: usize) -> Result<Vec<Range<usize>>, Error> {
+    let bytes = input.as_bytes();
+    let mut rv = Vec::new();
+    let mut start = None;
+    let mut idx = 0;
+    while idx < bytes.len() {
+        if bytes[idx].is_ascii_whitespace() {
+            if let Some(start) = start.take() {
+                rv.push(start..idx);
+            }
+            idx += 1;
+            continue;
+        }
+        start.get_or_insert(idx);
+        idx = match skip(input, idx, config, base)? {
+            Some(end) => end,
+            None => idx + 1,
+        };
+    }
+    if let Some(start) = start {
+        rv.push(start..bytes.len());
+    }
+    Ok(rv)
+}
+
+/// Finds the first separator that is not within quotes.
+pub(crate) fn find(
+    input: &str,
+    separator: &str,
+    config: &Config,
+    base: usize,
+) -> Result<Option<usize>, Error> {
+    let bytes = input.as_bytes();
+    let mut idx = 0;
+    while idx < bytes.len() {
+        if let Some(end) = skip(input, idx, config, base)? {
+            idx = end;
+        } else if bytes[idx..].starts_with(separator.as_bytes()) {
+            return Ok(Some(idx));
+        } else {
+            idx += 1;
+        }
+    }
+    Ok(None)
+}
+
+/// Removes the quotes around a value and resolves escape sequences.
+///
+/// Values that are not quoted are returned unchanged.
+pub(crate) fn unquote<'a>(
+    input: &'a str,
+    config: &Config,
+    base: usize,
+) -> Result<Cow<'a, str>, Error> {
+    if config.quoting == Quoting::Shell {
+        return unquote_shell(input, base);
+    }
+    if config.quoting != Quoting::DoubleQuotes || !input.starts_with('"') {
+        return Ok(Cow::Borrowed(input));
+    }
+    let end = skip_quoted(input, 0, base)?;
+    if end != input.len() {
+        return Err(Error::Parse(
+            "string",
+            format!(
+                "unexpected characters after closing quote at {}",
+                base + end
+            ),
+        ));
+    }
+
+    let inner = &input[1..end - 1];
+    if !inner.contains('\\') {
+        return Ok(Cow::Borrowed(inner));
+    }
+
+    let mut rv = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            rv.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('"') => rv.push('"'),
+            Some('\\') => rv.push('\\'),
+            Some('n') => rv.push('\n'),
+            Some('r') => rv.push('\r'),
+            Some('t') => rv.push('\t'),
+            Some('0') => rv.push('\0'),
+            Some('u') => {
+                let rest = chars.as_str();
+                let code = rest
+                    .strip_prefix('{')
+                    .and_then(|rest| rest.find('}').map(|end| &rest[..end]))
+                    .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                    .and_then(std::char::from_u32)
+                    .ok_or_else(|| Error::Parse("string", "invalid unicode escape".into()))?;
+                rv.push(code);
+                let len = rest.find('}').unwrap_or(0) + 1;
+                chars = rest[len..].chars();
+            }
+            other => {
+                return Err(Error::Parse(
+                    "string",
+                    format!("invalid escape sequence `\\{}`", other.unwrap_or(' ')),
+                ))
+            }
+        }
+    }
+    Ok(Cow::Owned(rv))
+}
+
+/// Resolves the quotes and escapes of a single shell word.
+fn unquote_shell(input: &str, base: usize) -> Result<Cow<'_, str>, Error> {
+    if !input.contains(|c: char| c == '\'' || c == '"' || c == '\\' || c.is_whitespace()) {
+        return Ok(Cow::Borrowed(input));
+    }
+    let mut rv = String::with_capacity(input.len());
+    let mut chars = input.char_indices().peekable();
+    while let Some((idx, c)) = chars.next() {
+        let unterminated = Error::UnterminatedQuote { offset: base + idx };
+        match c {
+            '\'' => loop {
+                match chars.next() {
+                    Some((_, '\'')) => break,
+                    Some((_, c)) => rv.push(c),
+                    None => return Err(unterminated),
+                }
+            },
+            '"' => loop {
+                match chars.next() {
+                    Some((_, '"')) => break,
+                    // within double quotes a backslash only escapes the
+                    // characters that are special there.
+                    Some((_, '\\')) => match chars.peek() {
+                        Some(&(_, c @ ('$' | '`' | '"' | '\\'))) => {
+                            rv.push(c);
+                            chars.next();
+                        }
+                        Some(&(_, '\n')) => {
+                            chars.next();
+                        }
+                        _ => rv.push('\\'),
+                    },
+                    Some((_, c)) => rv.push(c),
+                    None => return Err(unterminated),
+                }
+            },
+            '\\' => match chars.next() {
+                Some((_, '\n')) => {}
+                Some((_, c)) => rv.push(c),
+                None => rv.push('\\'),
+            },
+            c if c.is_whitespace() => {
+                return Err(Error::Parse(
+                    "string",
+                    format!("unquoted whitespace at {}", base + idx),
+                ))
+            }
+            c => rv.push(c),
+        }
+    }
+    Ok(Cow::Owned(rv))
+}
+
+/// Quotes a string if quoting is enabled and the value requires it.
+///
+/// A value is quoted if it is empty, has surrounding whitespace, contains
+/// one of the active separators, quotes, backslashes or control characters.
+pub(crate) fn quote<'a>(value: &'a str, config: &Config) -> Cow<'a, str> {
+    match config.quoting {
+        Quoting::Never => return Cow::Borrowed(value),
+        Quoting::DoubleQuotes => {}
+        Quoting::Shell => return quote_shell(value, config),
+    }
+    let needs_quoting = value.is_empty()
+        || value.starts_with(char::is_whitespace)
+        || value.ends_with(char::is_whitespace)
+        || value.contains(|c: char| c == '"' || c == '\\' || c.is_control())
+        || config.separators().any(|sep| value.contains(sep));
+    if !needs_quoting {
+        return Cow::Borrowed(value);
+    }
+
+    let mut rv = String::with_capacity(value.len() + 2);
+    rv.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => rv.push_str("\\\""),
+            '\\' => rv.push_str("\\\\"),
+            '\n' => rv.push_str("\\n"),
+            '\r' => rv.push_str("\\r"),
+            '\t' => rv.push_str("\\t"),
+            c if c.is_control() => write!(rv, "\\u{{{:x}}}", c as u32).unwrap(),
+            c => rv.push(c),
+        }
+    }
+    rv.push('"');
+    Cow::Owned(rv)
+}
+
+/// Writes a string as a shell word.
+///
+/// Words that only consist of characters without special meaning are
+/// written as they are, everything else is single-quoted.
+fn quote_shell<'a>(value: &'a str, config: &Config) -> Cow<'a, str> {
+    let is_safe = !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "_@%+=:,./-".contains(c))
+        && !config.separators().any(|sep| value.contains(sep));
+    if is_safe {
+        Cow::Borrowed(value)
+    } else {
+        Cow::Owned(format!("'{}'", value.replace('\'', "'\\''")))
+    }
+}
@@ -1,25 +1,173 @@
+use std::cmp::Ordering;
+use std::convert::TryInto;
+
 use serde::ser;
 
+use crate::bytes;
+use crate::config::{Config, DEFAULT};
 use crate::error::Error;
+use crate::float;
+use crate::pattern::Pattern;
+use crate::quote;
 
 /// A simple serializer that can dump out strings.
+///
+/// This uses the default configuration.  Use
+/// [`Serializer::with_config`] to serialize with a custom configuration.
+#[derive(Debug, Clone, Copy, Default)]
 pub struct Serializer;
 
+impl Serializer {
+    /// Creates a serializer that uses the given configuration.
+    pub fn with_config(config: &Config) -> ConfiguredSerializer<'_> {
+        ConfiguredSerializer { config }
+    }
+}
+
+/// A serializer that uses a configuration.
+///
+/// This is returned by [`Serializer::with_config`].
+#[derive(Debug, Clone, Copy)]
+pub struct ConfiguredSerializer<'a> {
+    config: &'a Config,
+}
+
+macro_rules! forward_to_configured {
+    ($($meth:ident($($arg:ident: $ty:ty),*);)*) => {
+        $(fn $meth(self, $($arg: $ty),*) -> Result<String, Error> {
+            ser::Serializer::$meth(Serializer::with_config(&DEFAULT), $($arg),*)
+        })*
+    };
+}
+
+impl ser::Serializer for Serializer {
+    type Ok = String;
+    type Error = Error;
+    type SerializeSeq = SeqSerializer<'static>;
+    type SerializeTuple = TupleSerializer<'static>;
+    type SerializeTupleStruct = TupleSerializer<'static>;
+    type SerializeTupleVariant = ser::Impossible<String, Error>;
+    type SerializeMap = MapSerializer<'static>;
+    type SerializeStruct = MapSerializer<'static>;
+    type SerializeStructVariant = ser::Impossible<String, Error>;
+
+    forward_to_configured! {
+        serialize_bool(v: bool);
+        serialize_i8(v: i8);
+        serialize_i16(v: i16);
+        serialize_i32(v: i32);
+        serialize_i64(v: i64);
+        serialize_u8(v: u8);
+        serialize_u16(v: u16);
+        serialize_u32(v: u32);
+        serialize_u64(v: u64);
+        serialize_f32(v: f32);
+        serialize_f64(v: f64);
+        serialize_char(v: char);
+        serialize_str(v: &str);
+        serialize_bytes(v: &[u8]);
+        serialize_none();
+        serialize_unit();
+        serialize_unit_struct(name: &'static str);
+        serialize_unit_variant(name: &'static str, variant_index: u32, variant: &'static str);
+    }
+
+    fn serialize_some<T: ?Sized + ser::Serialize>(self, value: &T) -> Result<String, Error> {
+        Serializer::with_config(&DEFAULT).serialize_some(value)
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + ser::Serialize>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<String, Error> {
+        Serializer::with_config(&DEFAULT).serialize_newtype_struct(name, value)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + ser::Serialize>(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<String, Error> {
+        Serializer::with_config(&DEFAULT).serialize_newtype_variant(
+            name,
+            variant_index,
+            variant,
+            value,
+        )
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SeqSerializer<'static>, Error> {
+        Serializer::with_config(&DEFAULT).serialize_seq(len)
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<TupleSerializer<'static>, Error> {
+        Serializer::with_config(&DEFAULT).serialize_tuple(len)
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<TupleSerializer<'static>, Error> {
+        Serializer::with_config(&DEFAULT).serialize_tuple_struct(name, len)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Error> {
+        Serializer::with_config(&DEFAULT).serialize_tuple_variant(name, variant_index, variant, len)
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<MapSerializer<'static>, Error> {
+        Serializer::with_config(&DEFAULT).serialize_map(len)
+    }
+
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<MapSerializer<'static>, Error> {
+        Serializer::with_config(&DEFAULT).serialize_struct(name, len)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, Error> {
+        Serializer::with_config(&DEFAULT).serialize_struct_variant(
+            name,
+            variant_index,
+            variant,
+            len,
+        )
+    }
+}
+
 macro_rules! serialize_as_string {
     ($($ty:ty => $meth:ident,)*) => {
         $(fn $meth(self, v: $ty) -> Result<String, Error> { Ok(v.to_string()) })*
     };
 }
 
-impl ser::Serializer for Serializer {
+impl<'a> ser::Serializer for ConfiguredSerializer<'a> {
     type Ok = String;
     type Error = Error;
-    type SerializeSeq = ser::Impossible<String, Error>;
-    type SerializeTuple = ser::Impossible<String, Error>;
-    type SerializeTupleStruct = ser::Impossible<String, Error>;
+    type SerializeSeq = SeqSerializer<'a>;
+    type SerializeTuple = TupleSerializer<'a>;
+    type SerializeTupleStruct = TupleSerializer<'a>;
     type SerializeTupleVariant = ser::Impossible<String, Error>;
-    type SerializeMap = ser::Impossible<String, Error>;
-    type SerializeStruct = ser::Impossible<String, Error>;
+    type SerializeMap = MapSerializer<'a>;
+    type SerializeStruct = MapSerializer<'a>;
     type SerializeStructVariant = ser::Impossible<String, Error>;
 
     serialize_as_string! {
@@ -32,31 +180,54 @@ impl ser::Serializer for Serializer {
         i16 => serialize_i16,
         i32 => serialize_i32,
         i64 => serialize_i64,
-        f32 => serialize_f32,
-        f64 => serialize_f64,
-        char => serialize_char,
-        &str => serialize_str,
     }
 
-    fn serialize_bytes(self, _value: &[u8]) -> Result<String, Error> {
-        Err(Error::ImpossibleSerialization("bytes"))
+    fn serialize_char(self, v: char) -> Result<String, Error> {
+        self.serialize_str(v.encode_utf8(&mut [0; 4]))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<String, Error> {
+        Ok(quote::quote(v, self.config).into_owned())
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<String, Error> {
+        float::format(v, self.config)
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<String, Error> {
+        float::format(v, self.config)
+    }
+
+    fn serialize_bytes(self, value: &[u8]) -> Result<String, Error> {
+        match self.config.byte_encoding {
+            Some(encoding) => Ok(bytes::encode(value, encoding)),
+            None => Err(Error::ImpossibleSerialization("bytes")),
+        }
     }
 
     fn serialize_unit(self) -> Result<String, Error> {
-        Ok("".to_string())
+        Ok(self.config.none_str().to_string())
     }
 
-    fn serialize_unit_struct(self, _name: &'static str) -> Result<String, Error> {
-        Err(Error::ImpossibleSerialization("unit struct"))
+    fn serialize_unit_struct(self, name: &'static str) -> Result<String, Error> {
+        if self.config.unit_struct_names {
+            Ok(name.to_string())
+        } else {
+            self.serialize_unit()
+        }
     }
 
     fn serialize_unit_variant(
         self,
         _name: &'static str,
-        _variant_index: u32,
+        variant_index: u32,
         variant: &'static str,
     ) -> Result<String, Error> {
-        Ok(variant.to_string())
+        if self.config.serialize_variant_index {
+            Ok(variant_index.to_string())
+        } else {
+            Ok(variant.to_string())
+        }
     }
 
     fn serialize_newtype_struct<T: ?Sized + ser::Serialize>(
@@ -78,19 +249,265 @@ impl ser::Serializer for Serializer {
     }
 
     fn serialize_none(self) -> Result<String, Error> {
-        Ok("".to_string())
+        Ok(self.config.none_str().to_string())
     }
 
     fn serialize_some<T: ?Sized + ser::Serialize>(self, value: &T) -> Result<String, Error> {
+        let rv = value.serialize(self)?;
+        if self.config.is_sentinel_run(&rv) {
+            Ok(format!("{}{}", self.config.none_str(), rv))
+        } else {
+            Ok(rv)
+        }
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Error> {
+        if self.config.seq_separator_in_effect().is_none() {
+            return Err(Error::ImpossibleSerialization("seq"));
+        }
+        Ok(SeqSerializer {
+            config: self.config,
+            items: Vec::with_capacity(len.unwrap_or(0)),
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Error> {
+        TupleSerializer::new(self.config, None, "tuple", len)
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Error> {
+        let pattern = self.config.pattern_for(name);
+        TupleSerializer::new(self.config, pattern, "tuple struct", len)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Error> {
+        Err(Error::ImpossibleSerialization("tuple variant"))
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, Error> {
+        MapSerializer::new(self.config, "map", len.unwrap_or(0))
+    }
+
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, Error> {
+        match self.config.pattern_for(name) {
+            Some(pattern) => MapSerializer::with_pattern(self.config, pattern, len),
+            None => MapSerializer::new(self.config, "struct", len),
+        }
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Error> {
+        Err(Error::ImpossibleSerialization("struct variant"))
+    }
+}
+
+/// Serializes sequences by joining their elements with a separator.
+///
+/// This is returned by [`Serializer`] when a sequence separator is
+/// configured.
+pub struct SeqSerializer<'a> {
+    config: &'a Config,
+    items: Vec<(SortKey, String)>,
+}
+
+impl<'a> ser::SerializeSeq for SeqSerializer<'a> {
+    type Ok = String;
+    type Error = Error;
+
+    fn serialize_element<T: ?Sized + ser::Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        let item = value.serialize(Serializer::with_config(self.config))?;
+        check_separator(&item, self.config.seq_separator_str(), self.config)?;
+        if self.config.seq_unique && self.items.iter().any(|(_, x)| *x == item) {
+            return Err(Error::Message(format!("duplicate element `{}`", item)));
+        }
+        let key = if self.config.seq_sorted {
+            value.serialize(SortKeySerializer).unwrap_or(SortKey::Text)
+        } else {
+            SortKey::Text
+        };
+        self.items.push((key, item));
+        Ok(())
+    }
+
+    fn end(mut self) -> Result<String, Error> {
+        if self.items.len() == 1 && self.items[0].1.is_empty() {
+            return Err(Error::Message(
+                "cannot represent a sequence with a single empty element".into(),
+            ));
+        }
+        if self.config.seq_sorted {
+            self.items
+                .sort_by(|(a, a_item), (b, b_item)| a.compare(*b).then_with(|| a_item.cmp(b_item)));
+        }
+        let items: Vec<_> = self.items.into_iter().map(|(_, item)| item).collect();
+        Ok(items.join(self.config.seq_separator_str()))
+    }
+}
+
+/// The key by which the elements of sorted sequences are ordered.
+///
+/// Elements of the same kind compare by their key, ties and everything
+/// else compare by their text.
+#[derive(Debug, Clone, Copy)]
+enum SortKey {
+    Variant(u32),
+    Integer(i128),
+    Float(f64),
+    Text,
+}
+
+impl SortKey {
+    fn rank(self) -> u8 {
+        match self {
+            SortKey::Variant(_) => 0,
+            SortKey::Integer(_) => 1,
+            SortKey::Float(_) => 2,
+            SortKey::Text => 3,
+        }
+    }
+
+    fn compare(self, other: SortKey) -> Ordering {
+        match (self, other) {
+            (SortKey::Variant(a), SortKey::Variant(b)) => a.cmp(&b),
+            (SortKey::Integer(a), SortKey::Integer(b)) => a.cmp(&b),
+            (SortKey::Float(a), SortKey::Float(b)) => a.total_cmp(&b),
+            _ => self.rank().cmp(&other.rank()),
+        }
+    }
+}
+
+/// Determines the sort key of a value from its type.
+///
+/// Values that are not unit variants or numbers fail and sort as text.
+struct SortKeySerializer;
+
+macro_rules! sort_as {
+    ($key:ident: $($ty:ty => $meth:ident,)*) => {
+        $(fn $meth(self, v: $ty) -> Result<SortKey, Error> {
+            Ok(SortKey::$key(v.into()))
+        })*
+    };
+}
+
+macro_rules! sort_as_text {
+    ($($ty:ty => $meth:ident,)*) => {
+        $(fn $meth(self, _v: $ty) -> Result<SortKey, Error> {
+            Ok(SortKey::Text)
+        })*
+    };
+}
+
+impl ser::Serializer for SortKeySerializer {
+    type Ok = SortKey;
+    type Error = Error;
+    type SerializeSeq = ser::Impossible<SortKey, Error>;
+    type SerializeTuple = ser::Impossible<SortKey, Error>;
+    type SerializeTupleStruct = ser::Impossible<SortKey, Error>;
+    type SerializeTupleVariant = ser::Impossible<SortKey, Error>;
+    type SerializeMap = ser::Impossible<SortKey, Error>;
+    type SerializeStruct = ser::Impossible<SortKey, Error>;
+    type SerializeStructVariant = ser::Impossible<SortKey, Error>;
+
+    sort_as! {
+        Integer:
+        u8  => serialize_u8,
+        u16 => serialize_u16,
+        u32 => serialize_u32,
+        u64 => serialize_u64,
+        i8  => serialize_i8,
+        i16 => serialize_i16,
+        i32 => serialize_i32,
+        i64 => serialize_i64,
+        i128 => serialize_i128,
+    }
+
+    sort_as! {
+        Float:
+        f32 => serialize_f32,
+        f64 => serialize_f64,
+    }
+
+    sort_as_text! {
+        bool => serialize_bool,
+        char => serialize_char,
+        &str => serialize_str,
+        &[u8] => serialize_bytes,
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<SortKey, Error> {
+        // values beyond `i128::MAX` all have 39 digits, so comparing their
+        // text orders them correctly.
+        Ok(SortKey::Integer(v.try_into().unwrap_or(i128::MAX)))
+    }
+
+    fn serialize_none(self) -> Result<SortKey, Error> {
+        Ok(SortKey::Text)
+    }
+
+    fn serialize_some<T: ?Sized + ser::Serialize>(self, value: &T) -> Result<SortKey, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<SortKey, Error> {
+        Ok(SortKey::Text)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<SortKey, Error> {
+        Ok(SortKey::Text)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+    ) -> Result<SortKey, Error> {
+        Ok(SortKey::Variant(variant_index))
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + ser::Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<SortKey, Error> {
         value.serialize(self)
     }
 
+    fn serialize_newtype_variant<T: ?Sized + ser::Serialize>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<SortKey, Error> {
+        Ok(SortKey::Text)
+    }
+
     fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Error> {
-        Err(Error::ImpossibleSerialization("seq"))
+        Err(Error::ImpossibleSerialization("sort key"))
     }
 
     fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Error> {
-        Err(Error::ImpossibleSerialization("tuple"))
+        Err(Error::ImpossibleSerialization("sort key"))
     }
 
     fn serialize_tuple_struct(
@@ -98,7 +515,7 @@ impl ser::Serializer for Serializer {
         _name: &'static str,
         _len: usize,
     ) -> Result<Self::SerializeTupleStruct, Error> {
-        Err(Error::ImpossibleSerialization("tuple struct"))
+        Err(Error::ImpossibleSerialization("sort key"))
     }
 
     fn serialize_tuple_variant(
@@ -108,11 +525,11 @@ impl ser::Serializer for Serializer {
         _variant: &'static str,
         _len: usize,
     ) -> Result<Self::SerializeTupleVariant, Error> {
-        Err(Error::ImpossibleSerialization("tuple variant"))
+        Err(Error::ImpossibleSerialization("sort key"))
     }
 
     fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Error> {
-        Err(Error::ImpossibleSerialization("map"))
+        Err(Error::ImpossibleSerialization("sort key"))
     }
 
     fn serialize_struct(
@@ -120,7 +537,7 @@ impl ser::Serializer for Serializer {
         _name: &'static str,
         _len: usize,
     ) -> Result<Self::SerializeStruct, Error> {
-        Err(Error::ImpossibleSerialization("struct"))
+        Err(Error::ImpossibleSerialization("sort key"))
     }
 
     fn serialize_struct_variant(
@@ -130,7 +547,206 @@ impl ser::Serializer for Serializer {
         _variant: &'static str,
         _len: usize,
     ) -> Result<Self::SerializeStructVariant, Error> {
-        Err(Error::ImpossibleSerialization("struct variant"))
+        Err(Error::ImpossibleSerialization("sort key"))
+    }
+}
+
+/// Serializes maps and structs as `key=value` entries joined by a separator.
+///
+/// This is returned by [`Serializer`] when a sequence separator is
+/// configured.  Structs with a format pattern are rendered through the
+/// pattern instead.
+pub struct MapSerializer<'a> {
+    config: &'a Config,
+    pattern: Option<&'a Pattern>,
+    key: Option<String>,
+    entries: Vec<(String, String)>,
+}
+
+impl<'a> MapSerializer<'a> {
+    fn new(config: &'a Config, kind: &'static str, len: usize) -> Result<MapSerializer<'a>, Error> {
+        if config.seq_separator_in_effect().is_none() {
+            return Err(Error::ImpossibleSerialization(kind));
+        }
+        Ok(MapSerializer {
+            config,
+            pattern: None,
+            key: None,
+            entries: Vec::with_capacity(len),
+        })
+    }
+
+    fn with_pattern(
+        config: &'a Config,
+        pattern: &'a Pattern,
+        len: usize,
+    ) -> Result<MapSerializer<'a>, Error> {
+        Ok(MapSerializer {
+            config,
+            pattern: Some(pattern),
+            key: None,
+            entries: Vec::with_capacity(len),
+        })
+    }
+
+    fn serializer(&self) -> ConfiguredSerializer<'a> {
+        Serializer::with_config(self.config)
+    }
+
+    fn push_entry(&mut self, key: &str, value: &str) -> Result<(), Error> {
+        if self.pattern.is_none() {
+            check_separator(key, self.config.seq_separator_str(), self.config)?;
+            check_separator(key, &self.config.key_value_separator, self.config)?;
+            check_separator(value, self.config.seq_separator_str(), self.config)?;
+        }
+        self.entries.push((key.to_string(), value.to_string()));
+        Ok(())
+    }
+
+    fn finish(self) -> Result<String, Error> {
+        if let Some(pattern) = self.pattern {
+            return pattern.render(&self.entries, self.config);
+        }
+        let entries: Vec<_> = self
+            .entries
+            .iter()
+            .map(|(key, value)| format!("{}{}{}", key, self.config.key_value_separator, value))
+            .collect();
+        Ok(entries.join(self.config.seq_separator_str()))
+    }
+}
+
+impl<'a> ser::SerializeMap for MapSerializer<'a> {
+    type Ok = String;
+    type Error = Error;
+
+    fn serialize_key<T: ?Sized + ser::Serialize>(&mut self, key: &T) -> Result<(), Error> {
+        self.key = Some(key.serialize(self.serializer())?);
+        Ok(())
+    }
+
+    fn serialize_value<T: ?Sized + ser::Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        let key = self
+            .key
+            .take()
+            .ok_or_else(|| Error::Message("value serialized before key".into()))?;
+        let value = value.serialize(self.serializer())?;
+        self.push_entry(&key, &value)
+    }
+
+    fn end(self) -> Result<String, Error> {
+        self.finish()
+    }
+}
+
+impl<'a> ser::SerializeStruct for MapSerializer<'a> {
+    type Ok = String;
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + ser::Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        let value = value.serialize(self.serializer())?;
+        self.push_entry(key, &value)
+    }
+
+    fn end(self) -> Result<String, Error> {
+        self.finish()
+    }
+}
+
+/// Serializes tuples and tuple structs by joining their fields with a
+/// separator.
+///
+/// This is returned by [`Serializer`] when a tuple separator is configured
+/// or a format pattern is registered for the tuple struct.
+pub struct TupleSerializer<'a> {
+    config: &'a Config,
+    pattern: Option<&'a Pattern>,
+    fields: Vec<String>,
+}
+
+impl<'a> TupleSerializer<'a> {
+    fn new(
+        config: &'a Config,
+        pattern: Option<&'a Pattern>,
+        kind: &'static str,
+        len: usize,
+    ) -> Result<TupleSerializer<'a>, Error> {
+        if pattern.is_none() && config.tuple_separator.is_none() {
+            return Err(Error::ImpossibleSerialization(kind));
+        }
+        Ok(TupleSerializer {
+            config,
+            pattern,
+            fields: Vec::with_capacity(len),
+        })
+    }
+
+    fn push_field<T: ?Sized + ser::Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        let field = value.serialize(Serializer::with_config(self.config))?;
+        if let (None, Some(separator)) = (self.pattern, &self.config.tuple_separator) {
+            check_separator(&field, separator, self.config)?;
+        }
+        self.fields.push(field);
+        Ok(())
+    }
+
+    fn finish(self) -> Result<String, Error> {
+        match self.pattern {
+            Some(pattern) => {
+                let values: Vec<_> = self
+                    .fields
+                    .into_iter()
+                    .enumerate()
+                    .map(|(idx, field)| (idx.to_string(), field))
+                    .collect();
+                pattern.render(&values, self.config)
+            }
+            None => Ok(self
+                .fields
+                .join(self.config.tuple_separator.as_deref().unwrap_or(""))),
+        }
+    }
+}
+
+impl<'a> ser::SerializeTuple for TupleSerializer<'a> {
+    type Ok = String;
+    type Error = Error;
+
+    fn serialize_element<T: ?Sized + ser::Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        self.push_field(value)
+    }
+
+    fn end(self) -> Result<String, Error> {
+        self.finish()
+    }
+}
+
+impl<'a> ser::SerializeTupleStruct for TupleSerializer<'a> {
+    type Ok = String;
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + ser::Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        self.push_field(value)
+    }
+
+    fn end(self) -> Result<String, Error> {
+        self.finish()
+    }
+}
+
+/// Fails if the value contains the separator outside of quotes.
+fn check_separator(value: &str, separator: &str, config: &Config) -> Result<(), Error> {
+    if quote::find(value, separator, config, 0)?.is_some() {
+        Err(Error::Message(format!(
+            "`{}` contains the separator `{}`",
+            value, separator
+        )))
+    } else {
+        Ok(())
     }
 }
 
@@ -142,3 +758,15 @@ impl ser::Serializer for Serializer {
 pub fn to_string<T: ser::Serialize>(value: &T) -> Result<String, Error> {
     value.serialize(Serializer)
 }
+
+/// Serialize the given data value as a plain string with a custom
+/// configuration.
+///
+/// This works like [`to_string`] but uses the representation described
+/// by the given [`Config`].
+pub fn to_string_with_config<T: ser::Serialize>(
+    value: &T,
+    config: &Config,
+) -> Result<String, Error> {
+    value.serialize(Serializer::with_config(config))
+}
@@ -0,0 +1,146 @@
+use std::fmt::Write;
+
+use serde::de::DeserializeOwned;
+use serde::ser::Serialize;
+
+use crate::config::Config;
+use crate::de::Deserializer;
+use crate::error::Error;
+use crate::ser::Serializer;
+
+/// The characters that are percent-encoded in a part of a URL.
+///
+/// Unreserved characters (letters, digits, `-`, `.`, `_` and `~`) are never
+/// encoded, non-ASCII characters are always encoded as UTF-8.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EncodeSet {
+    /// A segment of a path.  Everything but the characters allowed in
+    /// segments by RFC 3986 is encoded, most notably `/`, `?`, `#`, `%`
+    /// and spaces.  The dots of `.` and `..` are encoded as well so that
+    /// the segment is not resolved as a relative path.
+    #[default]
+    PathSegment,
+    /// A key or value of a query string.  Unlike in path segments `&`, `=`
+    /// and `+` are encoded while `/` and `?` are not.  When decoding, `+`
+    /// is read as a space.
+    Query,
+}
+
+impl EncodeSet {
+    fn is_allowed(self, b: u8) -> bool {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => true,
+            b'!' | b'$' | b'\'' | b'(' | b')' | b'*' | b',' | b';' | b':' | b'@' => true,
+            b'&' | b'=' | b'+' => self == EncodeSet::PathSegment,
+            b'/' | b'?' => self == EncodeSet::Query,
+            _ => false,
+        }
+    }
+}
+
+/// Serialize a value into a percent-encoded part of a URL.
+///
+/// The value is serialized like [`to_string`](crate::to_string) and
+/// every character that is not allowed in the given part of a URL is
+/// percent-encoded.
+///
+/// ```rust
+/// use serde_plain::EncodeSet;
+///
+/// let segment = serde_plain::to_url_segment(&"a/b c", EncodeSet::PathSegment).unwrap();
+/// assert_eq!(segment, "a%2Fb%20c");
+/// let value = serde_plain::to_url_segment(&"1+1=2", EncodeSet::Query).unwrap();
+/// assert_eq!(value, "1%2B1%3D2");
+/// ```
+pub fn to_url_segment<T: Serialize>(value: &T, set: EncodeSet) -> Result<String, Error> {
+    to_url_segment_with_config(value, set, &Config::default())
+}
+
+/// Like [`to_url_segment`] but with a custom configuration.
+pub fn to_url_segment_with_config<T: Serialize>(
+    value: &T,
+    set: EncodeSet,
+    config: &Config,
+) -> Result<String, Error> {
+    let plain = value.serialize(Serializer::with_config(config))?;
+    let is_dot_segment = set == EncodeSet::PathSegment && (plain == "." || plain == "..");
+    let mut rv = String::with_capacity(plain.len());
+    for &b in plain.as_bytes() {
+        if set.is_allowed(b) && !is_dot_segment {
+            rv.push(b as char);
+        } else {
+            write!(rv, "%{:02X}", b).unwrap();
+        }
+    }
+    Ok(rv)
+}
+
+/// Deserialize a value from a percent-encoded part of a URL.
+///
+/// The percent-encoding is decoded first and the result is parsed like
+/// [`from_str`](crate::from_str) does.  Decoded bytes must form valid
+/// UTF-8.
+///
+/// ```rust
+/// use serde_plain::EncodeSet;
+///
+/// let value: String = serde_plain::from_url_segment("a%2Fb%20c", EncodeSet::PathSegment).unwrap();
+/// assert_eq!(value, "a/b c");
+/// let value: String = serde_plain::from_url_segment("hello+world", EncodeSet::Query).unwrap();
+/// assert_eq!(value, "hello world");
+/// ```
+pub fn from_url_segment<T: DeserializeOwned>(s: &str, set: EncodeSet) -> Result<T, Error> {
+    from_url_segment_with_config(s, set, &Config::default())
+}
+
+/// Like [`from_url_segment`] but with a custom configuration.
+pub fn from_url_segment_with_config<T: DeserializeOwned>(
+    s: &str,
+    set: EncodeSet,
+    config: &Config,
+) -> Result<T, Error> {
+    let decoded = decode(s, set)?;
+    T::deserialize(Deserializer::with_config(&decoded, config))
+}
+
+/// Resolves percent-encoded bytes.
+fn decode(s: &str, set: EncodeSet) -> Result<String, Error> {
+    let bytes = s.as_bytes();
+    let mut rv = Vec::with_capacity(bytes.len());
+    let mut idx = 0;
+    while idx < bytes.len() {
+        match bytes[idx] {
+            b'%' => {
+                let byte = s
+                    .get(idx + 1..idx + 3)
+                    .filter(|hex| hex.bytes().all(|b| b.is_ascii_hexdigit()))
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                    .ok_or_else(|| {
+                        Error::Parse(
+                            "percent-encoding",
+                            format!("invalid escape at offset {}", idx),
+                        )
+                    })?;
+                rv.push(byte);
+                idx += 3;
+            }
+            b'+' if set == EncodeSet::Query => {
+                rv.push(b' ');
+                idx += 1;
+            }
+            b => {
+                rv.push(b);
+                idx += 1;
+            }
+        }
+    }
+    String::from_utf8(rv).map_err(|err| {
+        Error::Parse(
+            "percent-encoding",
+            format!(
+                "decoded value is not valid UTF-8 at byte {}",
+                err.utf8_error().valid_up_to()
+            ),
+        )
+    })
+}
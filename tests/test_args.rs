@@ -0,0 +1,323 @@
+#[macro_use]
+extern crate serde_derive;
+
+use serde_plain::{ArgCase, Config, Error};
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    Text,
+    Json,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct Options {
+    pub format: Format,
+    #[serde(default)]
+    pub verbose: bool,
+    #[serde(default)]
+    pub include: Vec<String>,
+    pub max_depth: Option<u32>,
+    pub offset: Option<i32>,
+}
+
+#[test]
+fn test_from_args() {
+    let args = [
+        "--format",
+        "json",
+        "--max-depth=3",
+        "--verbose",
+        "--include",
+        "src",
+        "--include=tests",
+        "--offset",
+        "-5",
+    ];
+    let opts: Options = serde_plain::from_args(args).unwrap();
+    assert_eq!(
+        opts,
+        Options {
+            format: Format::Json,
+            verbose: true,
+            include: vec!["src".into(), "tests".into()],
+            max_depth: Some(3),
+            offset: Some(-5),
+        }
+    );
+
+    let opts: Options = serde_plain::from_args(vec!["--format=text".to_string()]).unwrap();
+    assert!(!opts.verbose);
+    assert!(opts.include.is_empty());
+    assert_eq!(opts.max_depth, None);
+
+    let opts: Options = serde_plain::from_args(["--format=text", "--verbose=false"]).unwrap();
+    assert!(!opts.verbose);
+}
+
+#[test]
+fn test_from_args_with_config() {
+    #[derive(Deserialize, Debug, PartialEq)]
+    pub struct Filter {
+        pub tags: Vec<Vec<String>>,
+    }
+
+    let config = Config::new().seq_separator(",");
+    let filter: Filter =
+        serde_plain::from_args_with_config(["--tags", "a,b", "--tags", "c"], &config).unwrap();
+    assert_eq!(
+        filter.tags,
+        vec![
+            vec!["a".to_string(), "b".to_string()],
+            vec!["c".to_string()]
+        ]
+    );
+}
+
+#[test]
+fn test_from_args_errors() {
+    let err = serde_plain::from_args::<Options, _, _>(["--format", "xml"]).unwrap_err();
+    assert!(matches!(err, Error::Arg { ref flag, .. } if flag == "--format"));
+    assert!(
+        err.to_string().ends_with("for argument `--format`"),
+        "{}",
+        err
+    );
+
+    let err = serde_plain::from_args::<Options, _, _>(["--format"]).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "a value is required for argument `--format`"
+    );
+
+    let err = serde_plain::from_args::<Options, _, _>([
+        "--format=text",
+        "--max-depth=1",
+        "--max-depth=2",
+    ])
+    .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "given more than once for argument `--max-depth`"
+    );
+
+    let err = serde_plain::from_args::<Options, _, _>(["--verbose"]).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "missing field `format` for argument `--format`"
+    );
+
+    let err = serde_plain::from_args::<Options, _, _>(["json"]).unwrap_err();
+    assert_eq!(err.to_string(), "unexpected argument `json`");
+
+    let opts: Options = serde_plain::from_args(["--format=text", "--unknown"]).unwrap();
+    assert_eq!(opts.format, Format::Text);
+}
+
+#[test]
+fn test_to_args() {
+    let opts = Options {
+        format: Format::Json,
+        verbose: false,
+        include: vec!["src".into(), "--weird".into()],
+        max_depth: Some(3),
+        offset: Some(-5),
+    };
+    let args = serde_plain::to_args(&opts).unwrap();
+    assert_eq!(
+        args,
+        vec![
+            "--format",
+            "json",
+            "--include",
+            "src",
+            "--include=--weird",
+            "--max-depth",
+            "3",
+            "--offset",
+            "-5",
+        ]
+    );
+    assert_eq!(serde_plain::from_args::<Options, _, _>(args).unwrap(), opts);
+    assert!(serde_plain::to_args(&42).is_err());
+}
+
+#[test]
+fn test_to_args_with_config() {
+    let opts = Options {
+        format: Format::Text,
+        verbose: true,
+        include: vec![],
+        max_depth: None,
+        offset: Some(1),
+    };
+    let config = Config::new().arg_prefix("-").arg_case(ArgCase::Snake);
+    let args = serde_plain::to_args_with_config(&opts, &config).unwrap();
+    assert_eq!(args, vec!["-format", "text", "-verbose", "-offset", "1"]);
+    assert_eq!(
+        serde_plain::from_args_with_config::<Options, _, _>(args, &config).unwrap(),
+        opts
+    );
+
+    #[derive(Serialize)]
+    pub struct Nested {
+        pub inner: Options,
+    }
+    let err = serde_plain::to_args(&Nested { inner: opts }).unwrap_err();
+    assert!(matches!(err, Error::Arg { ref flag, .. } if flag == "--inner"));
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct Build {
+    pub release: bool,
+    pub features: Vec<String>,
+    pub jobs: Option<u32>,
+    pub target: String,
+}
+
+#[test]
+fn test_from_args_left_out_flags() {
+    let build: Build = serde_plain::from_args(["--target", "x86_64"]).unwrap();
+    assert_eq!(
+        build,
+        Build {
+            release: false,
+            features: vec![],
+            jobs: None,
+            target: "x86_64".into(),
+        }
+    );
+
+    let err = serde_plain::from_args::<Build, _, _>(["--release"]).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "missing field `target` for argument `--target`"
+    );
+    assert!(matches!(
+        err,
+        Error::Arg { ref error, .. } if matches!(**error, Error::MissingField("target"))
+    ));
+}
+
+#[test]
+fn test_from_args_left_out_flags_with_defaults_and_aliases() {
+    fn default_port() -> u16 {
+        8080
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    pub struct Serve {
+        #[serde(default = "default_port")]
+        port: u16,
+        #[serde(alias = "quiet")]
+        silent: bool,
+        watch: bool,
+    }
+
+    let serve: Serve = serde_plain::from_args(["--quiet"]).unwrap();
+    assert_eq!(
+        serve,
+        Serve {
+            port: 8080,
+            silent: true,
+            watch: false,
+        }
+    );
+    let serve: Serve = serde_plain::from_args(["--port", "1"]).unwrap();
+    assert_eq!(
+        serve,
+        Serve {
+            port: 1,
+            silent: false,
+            watch: false,
+        }
+    );
+}
+
+#[test]
+fn test_from_args_left_out_flags_take_precedence() {
+    fn yes() -> bool {
+        true
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    pub struct Color {
+        #[serde(default = "yes")]
+        color: bool,
+        #[serde(default = "yes")]
+        bold: bool,
+        width: Option<u16>,
+    }
+
+    let color: Color = serde_plain::from_args(["--bold=true"]).unwrap();
+    assert_eq!(
+        color,
+        Color {
+            color: false,
+            bold: true,
+            width: None,
+        }
+    );
+}
+
+#[test]
+fn test_args_roundtrip_without_defaults() {
+    let build = Build {
+        release: false,
+        features: vec![],
+        jobs: None,
+        target: "x86_64".into(),
+    };
+    let args = serde_plain::to_args(&build).unwrap();
+    assert_eq!(args, vec!["--target", "x86_64"]);
+    assert_eq!(serde_plain::from_args::<Build, _, _>(args).unwrap(), build);
+
+    let build = Build {
+        release: true,
+        features: vec!["a".into(), "b".into()],
+        jobs: Some(4),
+        target: "arm".into(),
+    };
+    let args = serde_plain::to_args(&build).unwrap();
+    assert_eq!(serde_plain::from_args::<Build, _, _>(args).unwrap(), build);
+}
+
+#[test]
+fn test_args_roundtrip_without_prefix() {
+    let build = Build {
+        release: true,
+        features: vec!["a".into()],
+        jobs: Some(2),
+        target: "x".into(),
+    };
+    let config = Config::new().arg_prefix("");
+    let args = serde_plain::to_args_with_config(&build, &config).unwrap();
+    assert_eq!(args, vec!["release", "features=a", "jobs=2", "target=x"]);
+    assert_eq!(
+        serde_plain::from_args_with_config::<Build, _, _>(args, &config).unwrap(),
+        build
+    );
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct Tristate {
+    pub color: Option<bool>,
+    pub tags: Option<Vec<String>>,
+}
+
+#[test]
+fn test_args_some_false_and_empty_read_as_none() {
+    let value = Tristate {
+        color: Some(false),
+        tags: Some(vec![]),
+    };
+    let args = serde_plain::to_args(&value).unwrap();
+    assert!(args.is_empty());
+    assert_eq!(
+        serde_plain::from_args::<Tristate, _, String>(args).unwrap(),
+        Tristate {
+            color: None,
+            tags: None,
+        }
+    );
+}
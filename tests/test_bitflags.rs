@@ -0,0 +1,74 @@
+#![cfg(feature = "bitflags")]
+#[macro_use]
+extern crate serde_derive;
+
+use serde_plain::Config;
+
+bitflags::bitflags! {
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub struct Perms: u8 {
+        const READ = 1;
+        const WRITE = 2;
+        const EXEC = 4;
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct Mode(#[serde(with = "serde_plain::bitflags")] Perms);
+
+fn flags() -> Config {
+    Config::new().seq_separator("|")
+}
+
+#[test]
+fn test_bitflags_roundtrip() {
+    let config = flags();
+    let mode = Mode(Perms::READ | Perms::WRITE | Perms::EXEC);
+    let s = serde_plain::to_string_with_config(&mode, &config).unwrap();
+    assert_eq!(s, "read|write|exec");
+    assert_eq!(
+        serde_plain::from_str_with_config::<Mode>(&s, &config).unwrap(),
+        mode
+    );
+    assert_eq!(
+        serde_plain::from_str_with_config::<Mode>("EXEC|Read", &config).unwrap(),
+        Mode(Perms::READ | Perms::EXEC)
+    );
+
+    let empty = Mode(Perms::empty());
+    assert_eq!(
+        serde_plain::to_string_with_config(&empty, &config).unwrap(),
+        ""
+    );
+    assert_eq!(
+        serde_plain::from_str_with_config::<Mode>("", &config).unwrap(),
+        empty
+    );
+}
+
+#[test]
+fn test_bitflags_options() {
+    let config = flags().seq_sorted(true);
+    assert_eq!(
+        serde_plain::to_string_with_config(&Mode(Perms::WRITE | Perms::EXEC), &config).unwrap(),
+        "exec|write"
+    );
+
+    let config = flags().seq_unique(true);
+    assert!(serde_plain::from_str_with_config::<Mode>("read|read", &config).is_err());
+    assert_eq!(
+        serde_plain::from_str_with_config::<Mode>("read|read", &flags()).unwrap(),
+        Mode(Perms::READ)
+    );
+
+    assert_eq!(
+        serde_plain::from_str_with_config::<Mode>("read|delete", &flags())
+            .unwrap_err()
+            .to_string(),
+        "unknown flag `delete`"
+    );
+    assert!(
+        serde_plain::to_string_with_config(&Mode(Perms::from_bits_retain(8)), &flags()).is_err()
+    );
+    assert!(serde_plain::to_string(&Mode(Perms::READ)).is_err());
+}
@@ -0,0 +1,112 @@
+use serde::de::{self, Deserialize, Deserializer, Visitor};
+use serde::ser::{Serialize, Serializer};
+use serde_plain::{ByteEncoding, Config, ParseMode};
+use std::{fmt, str};
+
+/// A byte string that serializes like `serde_bytes::ByteBuf`.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Digest(Vec<u8>);
+
+impl Serialize for Digest {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Digest {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Digest, D::Error> {
+        struct V;
+
+        impl<'de> Visitor<'de> for V {
+            type Value = Digest;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("bytes")
+            }
+
+            fn visit_byte_buf<E: de::Error>(self, value: Vec<u8>) -> Result<Digest, E> {
+                Ok(Digest(value))
+            }
+        }
+
+        deserializer.deserialize_byte_buf(V)
+    }
+}
+
+fn hex() -> Config {
+    Config::new().byte_encoding(ByteEncoding::Hex)
+}
+
+impl fmt::Display for Digest {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&serde_plain::to_string_with_config(self, &hex()).unwrap())
+    }
+}
+
+impl str::FromStr for Digest {
+    type Err = serde_plain::Error;
+    fn from_str(s: &str) -> Result<Digest, Self::Err> {
+        serde_plain::from_str_with_config(s, &hex())
+    }
+}
+
+#[test]
+fn test_bytes_unsupported_by_default() {
+    assert!(serde_plain::to_string(&Digest(vec![1])).is_err());
+    assert!(serde_plain::from_str::<Digest>("01").is_err());
+}
+
+#[test]
+fn test_hex() {
+    let digest = Digest(vec![0xde, 0xad, 0xbe, 0xef, 0x00]);
+    assert_eq!(digest.to_string(), "deadbeef00");
+    assert_eq!("deadbeef00".parse::<Digest>().unwrap(), digest);
+    assert_eq!("DEADBEEF00".parse::<Digest>().unwrap(), digest);
+    assert!("abc".parse::<Digest>().is_err());
+    assert!("zz".parse::<Digest>().is_err());
+
+    let upper = Config::new().byte_encoding(ByteEncoding::UpperHex);
+    assert_eq!(
+        serde_plain::to_string_with_config(&digest, &upper).unwrap(),
+        "DEADBEEF00"
+    );
+
+    let strict = hex().parse_mode(ParseMode::Canonical);
+    assert!(serde_plain::from_str_with_config::<Digest>("deadbeef00", &strict).is_ok());
+    assert!(serde_plain::from_str_with_config::<Digest>("DEADBEEF00", &strict).is_err());
+}
+
+#[test]
+fn test_base64() {
+    let cases: &[(&[u8], ByteEncoding, &str)] = &[
+        (b"", ByteEncoding::Base64, ""),
+        (b"f", ByteEncoding::Base64, "Zg=="),
+        (b"fo", ByteEncoding::Base64, "Zm8="),
+        (b"foo", ByteEncoding::Base64, "Zm9v"),
+        (b"foob", ByteEncoding::Base64NoPad, "Zm9vYg"),
+        (b"fooba", ByteEncoding::Base64NoPad, "Zm9vYmE"),
+        (b"\xde\xad\xbe\xef", ByteEncoding::Base64, "3q2+7w=="),
+        (b"\xde\xad\xbe\xef", ByteEncoding::Base64Url, "3q2-7w=="),
+        (b"\xde\xad\xbe\xef", ByteEncoding::Base64UrlNoPad, "3q2-7w"),
+    ];
+    for &(bytes, encoding, text) in cases {
+        let config = Config::new().byte_encoding(encoding);
+        let digest = Digest(bytes.to_vec());
+        assert_eq!(
+            serde_plain::to_string_with_config(&digest, &config).unwrap(),
+            text
+        );
+        assert_eq!(
+            serde_plain::from_str_with_config::<Digest>(text, &config).unwrap(),
+            digest
+        );
+    }
+
+    let config = Config::new().byte_encoding(ByteEncoding::Base64);
+    assert!(serde_plain::from_str_with_config::<Digest>("Zg", &config).is_err());
+    assert!(serde_plain::from_str_with_config::<Digest>("Zg=", &config).is_err());
+    assert!(serde_plain::from_str_with_config::<Digest>("3q2-7w==", &config).is_err());
+    let config = Config::new().byte_encoding(ByteEncoding::Base64NoPad);
+    assert!(serde_plain::from_str_with_config::<Digest>("Zg==", &config).is_err());
+    assert!(serde_plain::from_str_with_config::<Digest>("Z", &config).is_err());
+}
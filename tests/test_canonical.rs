@@ -0,0 +1,111 @@
+#[macro_use]
+extern crate serde_derive;
+
+use serde_plain::{Config, Error, NonCanonicalKind, ParseMode};
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Env {
+    #[serde(alias = "prod", alias = "Production")]
+    Production,
+    Staging,
+}
+
+fn kind_of(result: Result<(), Error>) -> NonCanonicalKind {
+    match result {
+        Err(Error::NonCanonical { kind, .. }) => kind,
+        other => panic!("unexpected result {:?}", other),
+    }
+}
+
+fn canonical(value: &str, is_canonical: bool) -> (String, bool) {
+    (value.to_string(), is_canonical)
+}
+
+#[test]
+fn test_canonicalize() {
+    assert_eq!(
+        serde_plain::canonicalize::<Env>("prod").unwrap(),
+        canonical("production", false)
+    );
+    assert_eq!(
+        serde_plain::canonicalize::<Env>("Production").unwrap(),
+        canonical("production", false)
+    );
+    assert_eq!(
+        serde_plain::canonicalize::<Env>("staging").unwrap(),
+        canonical("staging", true)
+    );
+    assert_eq!(
+        serde_plain::canonicalize::<i32>("+007").unwrap(),
+        canonical("7", false)
+    );
+    assert_eq!(
+        serde_plain::canonicalize::<f64>("1.50").unwrap(),
+        canonical("1.5", false)
+    );
+    assert_eq!(
+        serde_plain::canonicalize::<f64>("1.5").unwrap(),
+        canonical("1.5", true)
+    );
+    assert!(serde_plain::canonicalize::<Env>("dev").is_err());
+
+    let config = Config::new().parse_mode(ParseMode::Trimmed);
+    assert_eq!(
+        serde_plain::canonicalize_with_config::<u8>(" 3 ", &config).unwrap(),
+        canonical("3", false)
+    );
+}
+
+#[test]
+fn test_validate() {
+    assert!(serde_plain::validate::<Env>("production").is_ok());
+    assert!(serde_plain::validate::<u64>("42").is_ok());
+    assert_eq!(
+        kind_of(serde_plain::validate::<Env>("prod")),
+        NonCanonicalKind::Alias
+    );
+    assert_eq!(
+        kind_of(serde_plain::validate::<Env>("Production")),
+        NonCanonicalKind::Case
+    );
+    assert_eq!(
+        kind_of(serde_plain::validate::<u64>("042")),
+        NonCanonicalKind::Number
+    );
+    assert_eq!(
+        kind_of(serde_plain::validate_with_config::<u64>(
+            "42 ",
+            &Config::new().parse_mode(ParseMode::Trimmed)
+        )),
+        NonCanonicalKind::Whitespace
+    );
+    assert_eq!(
+        serde_plain::validate::<Env>("prod")
+            .unwrap_err()
+            .to_string(),
+        "non-canonical value `prod` (alternative spelling), expected `production`"
+    );
+    assert!(matches!(
+        serde_plain::validate::<Env>("dev"),
+        Err(Error::Message(_))
+    ));
+}
+
+#[test]
+fn test_canonical_mode_rejects_aliases() {
+    let strict = Config::new().parse_mode(ParseMode::Canonical);
+    assert_eq!(
+        serde_plain::canonicalize_with_config::<Env>("production", &strict).unwrap(),
+        canonical("production", true)
+    );
+    assert_eq!(
+        kind_of(serde_plain::canonicalize_with_config::<Env>("prod", &strict).map(|_| ())),
+        NonCanonicalKind::Alias
+    );
+    assert_eq!(
+        kind_of(serde_plain::canonicalize_with_config::<Env>("Production", &strict).map(|_| ())),
+        NonCanonicalKind::Case
+    );
+    assert!(serde_plain::canonicalize_with_config::<u32>("042", &strict).is_err());
+}
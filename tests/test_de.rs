@@ -1,7 +1,7 @@
 #[macro_use]
 extern crate serde_derive;
 
-use serde_plain::derive_fromstr_from_deserialize;
+use serde_plain::{derive_fromstr_from_deserialize, Config, Error, FloatFormat, ParseMode};
 
 use std::str::FromStr;
 
@@ -89,3 +89,307 @@ fn test_untagged_enum() {
 fn test_from_str() {
     assert_eq!("foo_bar_baz".parse::<Test>().unwrap(), Test::FooBarBaz);
 }
+
+#[test]
+fn test_float_non_finite() {
+    assert!(serde_plain::from_str::<f64>("inf").unwrap().is_infinite());
+    assert!(serde_plain::from_str::<f64>("NaN").unwrap().is_nan());
+
+    let config = Config::new()
+        .nan_repr("null")
+        .infinity_repr("+Inf")
+        .neg_infinity_repr("-Inf");
+    assert!(serde_plain::from_str_with_config::<f64>("null", &config)
+        .unwrap()
+        .is_nan());
+    assert_eq!(
+        serde_plain::from_str_with_config::<f64>("+Inf", &config).unwrap(),
+        f64::INFINITY
+    );
+    assert_eq!(
+        serde_plain::from_str_with_config::<f32>("-Inf", &config).unwrap(),
+        f32::NEG_INFINITY
+    );
+    assert_eq!(
+        serde_plain::from_str_with_config::<f64>("1.5e3", &config).unwrap(),
+        1500.0
+    );
+
+    let config = Config::new().allow_non_finite(false);
+    assert!(serde_plain::from_str_with_config::<f64>("inf", &config).is_err());
+    assert!(serde_plain::from_str_with_config::<f64>("NaN", &config).is_err());
+    assert_eq!(
+        serde_plain::from_str_with_config::<f64>("0.5", &config).unwrap(),
+        0.5
+    );
+}
+
+#[test]
+fn test_none_repr() {
+    let config = Config::new().none_repr("-");
+    let de = |s| serde_plain::from_str_with_config::<Option<Option<String>>>(s, &config).unwrap();
+    assert_eq!(de("-"), None);
+    assert_eq!(de("--"), Some(None));
+    assert_eq!(de(""), Some(Some("".into())));
+    assert_eq!(de("---"), Some(Some("-".into())));
+    assert_eq!(de("-x"), Some(Some("-x".into())));
+    assert_eq!(
+        serde_plain::from_str_with_config::<Option<String>>("--", &config).unwrap(),
+        Some("-".into())
+    );
+    serde_plain::from_str_with_config::<()>("-", &config).unwrap();
+    assert!(serde_plain::from_str_with_config::<()>("", &config).is_err());
+    assert_eq!(
+        serde_plain::from_str_with_config::<Option<u32>>("none", &Config::new().none_repr("none"))
+            .unwrap(),
+        None
+    );
+}
+
+#[test]
+fn test_parse_mode_trimmed() {
+    let config = Config::new().parse_mode(ParseMode::Trimmed);
+    assert!(serde_plain::from_str::<i32>(" 42").is_err());
+    assert_eq!(
+        serde_plain::from_str_with_config::<i32>(" 42\n", &config).unwrap(),
+        42
+    );
+    assert_eq!(
+        serde_plain::from_str_with_config::<Test>("\tblah_blah ", &config).unwrap(),
+        Test::BlahBlah
+    );
+    assert_eq!(
+        serde_plain::from_str_with_config::<&str>("  aha ", &config).unwrap(),
+        "aha"
+    );
+    assert_eq!(
+        serde_plain::from_str_with_config::<Option<u8>>("   ", &config).unwrap(),
+        None
+    );
+}
+
+#[test]
+fn test_parse_mode_canonical() {
+    let config = Config::new().parse_mode(ParseMode::Canonical);
+    let de_i32 = |s| serde_plain::from_str_with_config::<i32>(s, &config);
+    let de_f64 = |s| serde_plain::from_str_with_config::<f64>(s, &config);
+
+    assert_eq!(serde_plain::from_str::<i32>("+42").unwrap(), 42);
+    assert_eq!(de_i32("42").unwrap(), 42);
+    assert_eq!(de_i32("-7").unwrap(), -7);
+    assert!(de_i32("+42").is_err());
+    assert!(de_i32("042").is_err());
+    assert!(de_i32("-0").is_err());
+    assert!(de_i32(" 42").is_err());
+    assert_eq!(
+        de_i32("042").unwrap_err().to_string(),
+        "cannot parse i32: non-canonical representation, expected `42`"
+    );
+
+    assert_eq!(de_f64("1.5").unwrap(), 1.5);
+    assert_eq!(de_f64("1").unwrap(), 1.0);
+    assert!(de_f64("1.0").is_err());
+    assert!(de_f64("1.5e0").is_err());
+    assert!(de_f64("inf").is_ok());
+    assert!(de_f64("infinity").is_err());
+
+    let fixed = config.clone().float_format(FloatFormat::Fixed(2));
+    assert_eq!(
+        serde_plain::from_str_with_config::<f64>("1.50", &fixed).unwrap(),
+        1.5
+    );
+    assert!(serde_plain::from_str_with_config::<f64>("1.5", &fixed).is_err());
+
+    assert!(serde_plain::from_str_with_config::<bool>("true", &config).unwrap());
+    assert_eq!(
+        serde_plain::from_str_with_config::<&str>(" x ", &config).unwrap(),
+        " x "
+    );
+}
+
+#[derive(Deserialize, PartialEq, Eq, Debug)]
+pub struct Auto;
+
+#[derive(Deserialize, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Keyword {
+    Auto,
+    Fit,
+}
+
+#[derive(Deserialize, PartialEq, Eq, Debug)]
+#[serde(untagged)]
+pub enum Width {
+    Keyword(Keyword),
+    Other(String),
+}
+
+#[test]
+fn test_unit_struct() {
+    assert_eq!(serde_plain::from_str::<Auto>("").unwrap(), Auto);
+    assert!(serde_plain::from_str::<Auto>("Auto").is_err());
+    assert_eq!(
+        serde_plain::from_str_with_config::<Auto>("auto", &Config::new().none_repr("auto"))
+            .unwrap(),
+        Auto
+    );
+
+    let config = Config::new().unit_struct_names(true);
+    assert_eq!(
+        serde_plain::from_str_with_config::<Auto>("Auto", &config).unwrap(),
+        Auto
+    );
+    assert!(serde_plain::from_str_with_config::<Auto>("", &config).is_err());
+}
+
+#[test]
+fn test_untagged_empty_string() {
+    assert_eq!(
+        serde_plain::from_str::<Width>("").unwrap(),
+        Width::Other("".into())
+    );
+    assert_eq!(
+        serde_plain::from_str::<Width>("auto").unwrap(),
+        Width::Keyword(Keyword::Auto)
+    );
+}
+
+#[derive(Deserialize, PartialEq, Eq, Debug)]
+#[serde(untagged)]
+pub enum AutoWidth {
+    Auto(Auto),
+    Other(String),
+}
+
+#[derive(Deserialize, PartialEq, Eq, Debug)]
+#[serde(untagged)]
+pub enum AutoOnly {
+    Auto(Auto),
+}
+
+#[test]
+fn test_untagged_unit_struct() {
+    let config = Config::new().none_repr("auto");
+    let de = |s| serde_plain::from_str_with_config::<AutoWidth>(s, &config);
+    assert_eq!(de("auto").unwrap(), AutoWidth::Auto(Auto));
+    assert_eq!(de("80").unwrap(), AutoWidth::Other("80".into()));
+    assert_eq!(de("").unwrap(), AutoWidth::Other("".into()));
+    assert_eq!(
+        serde_plain::from_str_with_config::<AutoOnly>("auto", &config).unwrap(),
+        AutoOnly::Auto(Auto)
+    );
+    assert!(serde_plain::from_str_with_config::<AutoOnly>("80", &config).is_err());
+}
+
+#[derive(Deserialize, PartialEq, Eq, Debug)]
+pub struct Entries {
+    a: u32,
+    #[serde(flatten)]
+    rest: std::collections::BTreeMap<String, String>,
+}
+
+#[test]
+fn test_flatten_empty_value() {
+    let config = Config::new().seq_separator(",");
+    let entries: Entries = serde_plain::from_str_with_config("a=1,k=", &config).unwrap();
+    assert_eq!(entries.a, 1);
+    assert_eq!(entries.rest["k"], "");
+}
+
+#[derive(Deserialize, PartialEq, Eq, Debug)]
+pub enum Level {
+    Low,
+    Mid,
+    High,
+}
+
+#[test]
+fn test_variant_index() {
+    assert!(serde_plain::from_str::<Level>("2").is_err());
+
+    let config = Config::new().accept_variant_index(true);
+    let de = |s| serde_plain::from_str_with_config::<Level>(s, &config);
+    assert_eq!(de("0").unwrap(), Level::Low);
+    assert_eq!(de("2").unwrap(), Level::High);
+    assert_eq!(de("Mid").unwrap(), Level::Mid);
+    assert_eq!(
+        de("3").unwrap_err().to_string(),
+        "variant index 3 out of range for 3 variants"
+    );
+    assert!(de("-1").is_err());
+    assert_eq!(de("01").unwrap(), Level::Mid);
+    assert!(serde_plain::from_str_with_config::<Level>(
+        "01",
+        &config.clone().parse_mode(ParseMode::Canonical)
+    )
+    .is_err());
+}
+
+#[derive(Deserialize, PartialEq, Eq, Debug)]
+pub enum Digit {
+    Zero,
+    #[serde(rename = "0")]
+    Naught,
+}
+
+#[test]
+fn test_variant_index_prefers_names() {
+    let config = Config::new().accept_variant_index(true);
+    let de = |s| serde_plain::from_str_with_config::<Digit>(s, &config);
+    assert_eq!(de("0").unwrap(), Digit::Naught);
+    assert_eq!(de("1").unwrap(), Digit::Naught);
+    assert_eq!(de("Zero").unwrap(), Digit::Zero);
+}
+
+#[derive(Deserialize, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Channel {
+    Production,
+    Staging,
+    Stable,
+    Store,
+}
+
+#[test]
+fn test_variant_prefixes() {
+    assert!(serde_plain::from_str::<Channel>("prod").is_err());
+
+    let config = Config::new().variant_prefixes(true);
+    let de = |s| serde_plain::from_str_with_config::<Channel>(s, &config);
+    assert_eq!(de("prod").unwrap(), Channel::Production);
+    assert_eq!(de("p").unwrap(), Channel::Production);
+    assert_eq!(de("stag").unwrap(), Channel::Staging);
+    assert_eq!(de("stable").unwrap(), Channel::Stable);
+    assert!(de("").is_err());
+    assert!(de("dev").is_err());
+    assert!(de("productions").is_err());
+
+    match de("st") {
+        Err(Error::AmbiguousVariant { input, candidates }) => {
+            assert_eq!(input, "st");
+            assert_eq!(candidates, vec!["staging", "stable", "store"]);
+        }
+        other => panic!("unexpected result {:?}", other),
+    }
+    assert_eq!(
+        de("sta").unwrap_err().to_string(),
+        "ambiguous variant `sta`, could be `staging` or `stable`"
+    );
+    assert_eq!(
+        de("s").unwrap_err().to_string(),
+        "ambiguous variant `s`, could be `staging`, `stable` or `store`"
+    );
+
+    let strict = config.clone().parse_mode(ParseMode::Canonical);
+    assert!(serde_plain::from_str_with_config::<Channel>("prod", &strict).is_err());
+}
+
+#[test]
+fn test_deserializer_values() {
+    use serde::Deserialize;
+    use serde_plain::Deserializer;
+
+    assert_eq!(u32::deserialize(Deserializer::new("42")).unwrap(), 42);
+    let config = Config::new().allow_non_finite(false);
+    assert!(f64::deserialize(Deserializer::with_config("inf", &config)).is_err());
+}
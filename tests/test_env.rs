@@ -0,0 +1,217 @@
+#[macro_use]
+extern crate serde_derive;
+
+use serde_plain::{Config, Error};
+use std::collections::HashMap;
+
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Level {
+    Debug,
+    Info,
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+pub struct Database {
+    pub host: String,
+    pub port: u16,
+    pub pool_size: Option<u32>,
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+pub struct Settings {
+    pub log_level: Level,
+    #[serde(default)]
+    pub workers: u32,
+    pub tags: Option<Vec<String>>,
+    pub db: Database,
+}
+
+fn vars(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+    pairs
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
+
+#[test]
+fn test_from_vars() {
+    let vars = vars(&[
+        ("APP_LOG_LEVEL", "debug"),
+        ("APP_DB__HOST", "localhost"),
+        ("APP_DB__PORT", "5432"),
+        ("APP_DB__POOL_SIZE", "8"),
+        ("OTHER_WORKERS", "3"),
+    ]);
+    let settings: Settings = serde_plain::from_vars("APP", vars).unwrap();
+    assert_eq!(
+        settings,
+        Settings {
+            log_level: Level::Debug,
+            workers: 0,
+            tags: None,
+            db: Database {
+                host: "localhost".into(),
+                port: 5432,
+                pool_size: Some(8),
+            },
+        }
+    );
+}
+
+#[test]
+fn test_from_vars_with_config() {
+    let vars = vars(&[
+        ("LOG_LEVEL", "info"),
+        ("WORKERS", "4"),
+        ("TAGS", "a,b"),
+        ("DB__HOST", "db"),
+        ("DB__PORT", "1"),
+    ]);
+    let config = Config::new().seq_separator(",");
+    let settings: Settings = serde_plain::from_vars_with_config("", vars, &config).unwrap();
+    assert_eq!(settings.workers, 4);
+    assert_eq!(settings.tags, Some(vec!["a".to_string(), "b".to_string()]));
+    assert_eq!(settings.db.pool_size, None);
+}
+
+#[test]
+fn test_from_vars_errors_name_variable() {
+    let vars = vars(&[
+        ("APP_LOG_LEVEL", "debug"),
+        ("APP_DB__HOST", "localhost"),
+        ("APP_DB__PORT", "http"),
+    ]);
+    let err = serde_plain::from_vars::<Settings, _, _, _>("APP", vars).unwrap_err();
+    assert!(matches!(err, Error::Env { ref var, .. } if var == "APP_DB__PORT"));
+    assert_eq!(
+        err.to_string(),
+        "cannot parse u16: invalid digit found in string in environment variable `APP_DB__PORT`"
+    );
+
+    let vars = self::vars(&[("APP_DB__HOST", "localhost"), ("APP_DB__PORT", "1")]);
+    let err = serde_plain::from_vars::<Settings, _, _, _>("APP", vars).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "missing field `log_level` in environment variable `APP_LOG_LEVEL`"
+    );
+}
+
+#[test]
+fn test_from_env() {
+    // the prefix is unique to this process and the variables are removed
+    // again, so the environment is left as it was found
+    struct Vars(Vec<String>);
+
+    impl Drop for Vars {
+        fn drop(&mut self) {
+            for key in &self.0 {
+                std::env::remove_var(key);
+            }
+        }
+    }
+
+    let prefix = format!("SERDE_PLAIN_TEST_{}", std::process::id());
+    let mut vars = Vars(Vec::new());
+    for (field, value) in &[
+        ("LOG_LEVEL", "info"),
+        ("DB__HOST", "example.com"),
+        ("DB__PORT", "80"),
+    ] {
+        let key = format!("{}_{}", prefix, field);
+        std::env::set_var(&key, value);
+        vars.0.push(key);
+    }
+
+    let settings: Settings = serde_plain::from_env(&prefix).unwrap();
+    assert_eq!(settings.log_level, Level::Info);
+    assert_eq!(settings.db.host, "example.com");
+    assert_eq!(settings.db.port, 80);
+
+    drop(vars);
+    assert!(serde_plain::from_env::<Settings>(&prefix).is_err());
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct Endpoint {
+    pub host: String,
+    pub port: u16,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct Service {
+    pub name: String,
+    pub replicas: Option<u32>,
+    pub labels: Vec<String>,
+    pub endpoint: Endpoint,
+    pub backup: Option<Endpoint>,
+}
+
+#[test]
+fn test_to_env_pairs_roundtrip() {
+    let config = Config::new()
+        .seq_separator(",")
+        .pattern("Endpoint", "{host}:{port}")
+        .unwrap();
+    let service = Service {
+        name: "api".into(),
+        replicas: None,
+        labels: vec!["a".into(), "b".into()],
+        endpoint: Endpoint {
+            host: "localhost".into(),
+            port: 80,
+        },
+        backup: None,
+    };
+    let pairs = serde_plain::to_env_pairs_with_config(&service, "SVC", &config).unwrap();
+    assert_eq!(
+        pairs,
+        vec![
+            ("SVC_NAME".to_string(), "api".to_string()),
+            ("SVC_LABELS".to_string(), "a,b".to_string()),
+            ("SVC_ENDPOINT".to_string(), "localhost:80".to_string()),
+        ]
+    );
+    let back: Service = serde_plain::from_vars_with_config("SVC", pairs, &config).unwrap();
+    assert_eq!(back, service);
+}
+
+#[test]
+fn test_to_env_pairs_nested() {
+    let service = Service {
+        name: "api".into(),
+        replicas: Some(3),
+        labels: vec![],
+        endpoint: Endpoint {
+            host: "localhost".into(),
+            port: 80,
+        },
+        backup: Some(Endpoint {
+            host: "backup".into(),
+            port: 81,
+        }),
+    };
+    let config = Config::new().seq_separator(",");
+    let pairs = serde_plain::to_env_pairs_with_config(&service, "", &config).unwrap();
+    assert_eq!(
+        pairs
+            .iter()
+            .map(|(key, _)| key.as_str())
+            .collect::<Vec<_>>(),
+        vec![
+            "NAME",
+            "REPLICAS",
+            "LABELS",
+            "ENDPOINT__HOST",
+            "ENDPOINT__PORT",
+            "BACKUP__HOST",
+            "BACKUP__PORT",
+        ]
+    );
+    let back: Service = serde_plain::from_vars_with_config("", pairs, &config).unwrap();
+    assert_eq!(back, service);
+
+    let err = serde_plain::to_env_pairs(&service, "SVC").unwrap_err();
+    assert!(matches!(err, Error::Env { ref var, .. } if var == "SVC_LABELS"));
+    assert!(serde_plain::to_env_pairs(&42, "").is_err());
+}
@@ -0,0 +1,76 @@
+#[macro_use]
+extern crate serde_derive;
+
+use serde_plain::{Config, Error, Quoting};
+use std::error::Error as _;
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Limits {
+    pub min: u32,
+    pub max: u32,
+}
+
+fn config() -> Config {
+    Config::new().seq_separator(",")
+}
+
+#[test]
+fn test_seq_error_location() {
+    let input = "1,2,x,4";
+    let err = serde_plain::from_str_with_config::<Vec<u32>>(input, &config()).unwrap_err();
+    assert_eq!(err.path(), Some("[2]"));
+    assert_eq!(err.offset(), Some(4));
+    assert_eq!(err.span(), Some(4..5));
+    assert_eq!(
+        err.to_string(),
+        "cannot parse u32: invalid digit found in string at `[2]` (offset 4)"
+    );
+    assert_eq!(err.render(input).unwrap(), "1,2,x,4\n    ^");
+    match err {
+        Error::Located { ref error, .. } => assert!(matches!(**error, Error::Parse("u32", _))),
+        ref other => panic!("unexpected error {:?}", other),
+    }
+}
+
+#[test]
+fn test_error_chain() {
+    let input = "min=1,max=x";
+    let err = serde_plain::from_str_with_config::<Limits>(input, &config()).unwrap_err();
+    let mut messages = vec![err.to_string()];
+    let mut source = err.source();
+    while let Some(error) = source {
+        messages.push(error.to_string());
+        source = error.source();
+    }
+    assert_eq!(
+        messages,
+        vec!["cannot parse u32: invalid digit found in string at `max` (offset 10)"]
+    );
+}
+
+#[test]
+fn test_struct_error_location() {
+    let input = "min=1,max=1x0";
+    let err = serde_plain::from_str_with_config::<Limits>(input, &config()).unwrap_err();
+    assert_eq!(err.path(), Some("max"));
+    assert_eq!(err.span(), Some(10..13));
+    assert_eq!(err.render(input).unwrap(), "min=1,max=1x0\n          ^^^");
+
+    let err = serde_plain::from_str_with_config::<Limits>("min=1,mux=2", &config()).unwrap_err();
+    assert_eq!(err.path(), Some("mux"));
+    assert_eq!(err.span(), Some(6..9));
+
+    let err = serde_plain::from_str_with_config::<Limits>("min=1", &config()).unwrap_err();
+    assert_eq!(err.path(), None);
+    assert_eq!(err.render("min=1"), None);
+}
+
+#[test]
+fn test_render_unicode() {
+    let config = config().quoting(Quoting::DoubleQuotes);
+    let input = "äöü,\"x";
+    let err = serde_plain::from_str_with_config::<Vec<String>>(input, &config).unwrap_err();
+    assert_eq!(err.offset(), Some(7));
+    assert_eq!(err.render(input).unwrap(), "äöü,\"x\n    ^");
+}
@@ -0,0 +1,307 @@
+#[macro_use]
+extern crate serde_derive;
+
+use serde_plain::http_header;
+use serde_plain::Config;
+use std::collections::BTreeMap;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct Quality {
+    pub q: Option<f32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Default)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct CacheControl {
+    pub max_age: Option<u32>,
+    pub no_cache: bool,
+    pub private: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Encoding {
+    Gzip,
+    Br,
+    Identity,
+}
+
+#[test]
+fn test_lists() {
+    let encodings: Vec<Encoding> = http_header::from_str("gzip, br ,, identity").unwrap();
+    assert_eq!(
+        encodings,
+        vec![Encoding::Gzip, Encoding::Br, Encoding::Identity]
+    );
+    assert_eq!(
+        http_header::to_string(&encodings).unwrap(),
+        "gzip, br, identity"
+    );
+
+    let etags: Vec<String> = http_header::from_str(r#""a,b", "x\"y", plain"#).unwrap();
+    assert_eq!(etags, vec!["a,b", "x\"y", "plain"]);
+    assert_eq!(
+        http_header::to_string(&etags).unwrap(),
+        r#""a,b", "x\"y", plain"#
+    );
+
+    let empty: Option<Vec<String>> = http_header::from_str("  ").unwrap();
+    assert_eq!(empty, None);
+}
+
+#[test]
+fn test_params() {
+    let accept: Vec<(String, Quality)> =
+        http_header::from_str("text/html;q=0.9, application/json , */*; Q=\"0.1\"").unwrap();
+    assert_eq!(
+        accept,
+        vec![
+            ("text/html".into(), Quality { q: Some(0.9) }),
+            ("application/json".into(), Quality { q: None }),
+            ("*/*".into(), Quality { q: Some(0.1) }),
+        ]
+    );
+    assert_eq!(
+        http_header::to_string(&accept).unwrap(),
+        "text/html;q=0.9, application/json, */*;q=0.1"
+    );
+
+    let content_type: (String, BTreeMap<String, String>) =
+        http_header::from_str("multipart/form-data; boundary=\"a b\"; charset=utf-8").unwrap();
+    assert_eq!(content_type.0, "multipart/form-data");
+    assert_eq!(content_type.1["boundary"], "a b");
+    assert_eq!(
+        http_header::to_string(&content_type).unwrap(),
+        "multipart/form-data;boundary=\"a b\";charset=utf-8"
+    );
+}
+
+#[test]
+fn test_params_without_value() {
+    let err = http_header::from_str::<(String, Quality)>(";").unwrap_err();
+    assert_eq!(err.path(), Some("[0]"));
+    assert_eq!(err.span(), Some(0..1));
+    assert_eq!(
+        err.to_string(),
+        "missing value before the parameters at `[0]` (offset 0)"
+    );
+
+    let err = http_header::from_str::<Vec<(String, Quality)>>("a, ;").unwrap_err();
+    assert_eq!(err.path(), Some("[1][0]"));
+    assert_eq!(err.span(), Some(3..4));
+}
+
+#[test]
+fn test_directives() {
+    let cc: CacheControl = http_header::from_str("no-cache, Max-Age=60").unwrap();
+    assert_eq!(
+        cc,
+        CacheControl {
+            max_age: Some(60),
+            no_cache: true,
+            private: false,
+        }
+    );
+    assert_eq!(http_header::to_string(&cc).unwrap(), "max-age=60, no-cache");
+
+    let map: BTreeMap<String, u32> = http_header::from_str("a=1, b=2;c=3").unwrap();
+    assert_eq!(map.len(), 3);
+    assert_eq!(map["c"], 3);
+
+    let mut map = BTreeMap::new();
+    map.insert("a", "true");
+    assert_eq!(http_header::to_string(&map).unwrap(), "a=true");
+
+    let mut map = BTreeMap::new();
+    map.insert("bad name", 1);
+    assert!(http_header::to_string(&map).is_err());
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub struct Directives {
+    pub max_age: Option<u32>,
+    pub no_store: bool,
+    pub private: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct Flags {
+    pub q: Option<f32>,
+    pub strict: bool,
+}
+
+#[test]
+fn test_directives_roundtrip_without_defaults() {
+    let directives = Directives {
+        max_age: None,
+        no_store: true,
+        private: false,
+    };
+    let s = http_header::to_string(&directives).unwrap();
+    assert_eq!(s, "no-store");
+    assert_eq!(http_header::from_str::<Directives>(&s).unwrap(), directives);
+
+    let empty = Directives {
+        max_age: None,
+        no_store: false,
+        private: false,
+    };
+    assert_eq!(http_header::to_string(&empty).unwrap(), "");
+    assert_eq!(http_header::from_str::<Directives>("").unwrap(), empty);
+
+    let accept = vec![
+        (
+            "a".to_string(),
+            Flags {
+                q: Some(0.5),
+                strict: false,
+            },
+        ),
+        (
+            "b".to_string(),
+            Flags {
+                q: None,
+                strict: true,
+            },
+        ),
+    ];
+    let s = http_header::to_string(&accept).unwrap();
+    assert_eq!(s, "a;q=0.5, b;strict");
+    assert_eq!(
+        http_header::from_str::<Vec<(String, Flags)>>(&s).unwrap(),
+        accept
+    );
+
+    let lists: Lists = http_header::from_str("q=1").unwrap();
+    assert!(lists.tags.is_empty());
+    let err = http_header::from_str::<Lists>("").unwrap_err();
+    assert_eq!(err.to_string(), "missing field `q`");
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Lists {
+    pub tags: Vec<u32>,
+    pub q: f32,
+}
+
+#[test]
+fn test_left_out_params() {
+    fn default_level() -> u8 {
+        6
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    pub struct Compression {
+        #[serde(default = "default_level")]
+        level: u8,
+        #[serde(alias = "fast")]
+        quick: bool,
+    }
+
+    let s = vec!["a"; 1000].join(",");
+    let flags = http_header::from_str::<Vec<(String, Flags)>>(&s).unwrap();
+    assert_eq!(flags.len(), 1000);
+    assert!(flags
+        .iter()
+        .all(|(_, flags)| !flags.strict && flags.q.is_none()));
+
+    let compression: Vec<(String, Compression)> =
+        http_header::from_str("gzip;fast, br;level=4;quick").unwrap();
+    assert_eq!(
+        compression,
+        vec![
+            (
+                "gzip".to_string(),
+                Compression {
+                    level: 6,
+                    quick: true,
+                },
+            ),
+            (
+                "br".to_string(),
+                Compression {
+                    level: 4,
+                    quick: true,
+                },
+            ),
+        ]
+    );
+    let compression: Vec<(String, Compression)> = http_header::from_str("zstd").unwrap();
+    assert_eq!(
+        compression,
+        vec![(
+            "zstd".to_string(),
+            Compression {
+                level: 6,
+                quick: false,
+            },
+        )]
+    );
+}
+
+#[test]
+fn test_scalars() {
+    let date: String = http_header::from_str(" Tue, 15 Nov 1994 08:12:31 GMT ").unwrap();
+    assert_eq!(date, "Tue, 15 Nov 1994 08:12:31 GMT");
+    assert_eq!(http_header::from_str::<u64>("1024").unwrap(), 1024);
+    assert_eq!(http_header::to_string(&1024).unwrap(), "1024");
+
+    let config = Config::new().seq_separator(" ");
+    let tags: Vec<(Vec<u32>, Quality)> =
+        http_header::from_str_with_config("1 2;q=1, 3", &config).unwrap();
+    assert_eq!(tags[0].0, vec![1, 2]);
+    assert_eq!(tags[1].0, vec![3]);
+}
+
+#[test]
+fn test_errors() {
+    let input = "text/html;q=high, */*";
+    let err = http_header::from_str::<Vec<(String, Quality)>>(input).unwrap_err();
+    assert_eq!(err.path(), Some("[0].q"));
+    assert_eq!(err.span(), Some(12..16));
+
+    let input = "gzip, zstd";
+    let err = http_header::from_str::<Vec<Encoding>>(input).unwrap_err();
+    assert_eq!(err.path(), Some("[1]"));
+    assert_eq!(err.render(input).unwrap(), "gzip, zstd\n      ^^^^");
+
+    assert!(http_header::from_str::<Vec<String>>("\"open, x").is_err());
+    assert!(http_header::from_str::<(String, Quality)>("a, b").is_err());
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct Range {
+    pub start: u32,
+    pub end: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct Nested {
+    pub range: Option<Range>,
+    pub q: Option<f32>,
+}
+
+#[test]
+fn test_nested_params() {
+    let value = (
+        "text/html".to_string(),
+        Nested {
+            range: Some(Range { start: 1, end: 2 }),
+            q: Some(0.5),
+        },
+    );
+    let err = http_header::to_string(&value).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "cannot serialize non primitive type struct for key `range`"
+    );
+
+    let config = Config::new().pattern("Range", "{start}-{end}").unwrap();
+    let s = http_header::to_string_with_config(&value, &config).unwrap();
+    assert_eq!(s, "text/html;range=1-2;q=0.5");
+    assert_eq!(
+        http_header::from_str_with_config::<(String, Nested)>(&s, &config).unwrap(),
+        value
+    );
+}
@@ -0,0 +1,75 @@
+#[macro_use]
+extern crate serde_derive;
+
+use serde_plain::{Config, Error};
+use std::io::{self, BufRead, BufReader, Read};
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum State {
+    Running,
+    Stopped,
+}
+
+#[test]
+fn test_read_lines() {
+    let input = "running\r\nstopped\nrunning";
+    let states: Vec<State> = serde_plain::from_reader_lines(input.as_bytes())
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(states, vec![State::Running, State::Stopped, State::Running]);
+}
+
+#[test]
+fn test_read_lines_error_line() {
+    let input = "running\npaused\nstopped\n";
+    let mut lines = serde_plain::from_reader_lines::<_, State>(input.as_bytes());
+    assert!(lines.next().unwrap().is_ok());
+    let err = lines.next().unwrap().unwrap_err();
+    assert_eq!(err.line(), Some(2));
+    assert!(err.to_string().ends_with(" on line 2"), "{}", err);
+    assert_eq!(lines.next().unwrap().unwrap(), State::Stopped);
+    assert!(lines.next().is_none());
+}
+
+#[test]
+fn test_read_lines_with_config() {
+    let config = Config::new().seq_separator(",");
+    let input = "1,2\n3\n";
+    let values: Vec<Vec<u32>> =
+        serde_plain::from_reader_lines_with_config(input.as_bytes(), &config)
+            .collect::<Result<_, _>>()
+            .unwrap();
+    assert_eq!(values, vec![vec![1, 2], vec![3]]);
+}
+
+struct Failing;
+
+impl Read for Failing {
+    fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+        Err(io::Error::new(io::ErrorKind::Other, "disk on fire"))
+    }
+}
+
+#[test]
+fn test_read_lines_io_error() {
+    let reader: Box<dyn BufRead> = Box::new(BufReader::new(Failing));
+    let mut lines = serde_plain::from_reader_lines::<_, u32>(reader);
+    let err = lines.next().unwrap().unwrap_err();
+    match err {
+        Error::Line { line: 1, ref error } => assert!(matches!(**error, Error::Io(_))),
+        ref other => panic!("unexpected error {:?}", other),
+    }
+    assert!(lines.next().is_none());
+}
+
+#[test]
+fn test_write_lines() {
+    let mut out = Vec::new();
+    serde_plain::to_writer_lines(&mut out, &[State::Running, State::Stopped]).unwrap();
+    assert_eq!(out, b"running\nstopped\n");
+
+    let mut out = Vec::new();
+    let err = serde_plain::to_writer_lines(&mut out, &["a", "b\nc"]).unwrap_err();
+    assert_eq!(err.line(), Some(2));
+}
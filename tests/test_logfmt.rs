@@ -0,0 +1,135 @@
+#[macro_use]
+extern crate serde_derive;
+
+use serde_plain::logfmt;
+use serde_plain::{Config, Error};
+use std::collections::BTreeMap;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Level {
+    Info,
+    Warn,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct Record {
+    pub level: Level,
+    pub msg: String,
+    pub port: Option<u16>,
+    pub cached: bool,
+}
+
+#[test]
+fn test_from_str() {
+    let record: Record =
+        logfmt::from_str("level=warn msg=\"disk \\\"sda\\\" at 90%\" cached  port=8080\n").unwrap();
+    assert_eq!(
+        record,
+        Record {
+            level: Level::Warn,
+            msg: "disk \"sda\" at 90%".into(),
+            port: Some(8080),
+            cached: true,
+        }
+    );
+
+    let map: BTreeMap<String, u32> = logfmt::from_str("a=1 b=2 a=3").unwrap();
+    assert_eq!(
+        map.into_iter().collect::<Vec<_>>(),
+        vec![("a".into(), 3), ("b".into(), 2)]
+    );
+
+    let map: BTreeMap<String, String> = logfmt::from_str("a= b=\"\" c=x=y").unwrap();
+    assert_eq!(map["a"], "");
+    assert_eq!(map["b"], "");
+    assert_eq!(map["c"], "x=y");
+}
+
+#[test]
+fn test_to_string() {
+    let record = Record {
+        level: Level::Info,
+        msg: "started".into(),
+        port: None,
+        cached: false,
+    };
+    assert_eq!(
+        logfmt::to_string(&record).unwrap(),
+        "level=info msg=started cached=false"
+    );
+
+    let mut map = BTreeMap::new();
+    map.insert("empty", "");
+    map.insert("path", "C:\\temp dir");
+    map.insert("text", "a=b\n\x07");
+    map.insert("unicode", "grüße");
+    let line = logfmt::to_string(&map).unwrap();
+    assert_eq!(
+        line,
+        "empty=\"\" path=\"C:\\\\temp dir\" text=\"a=b\\n\\u0007\" unicode=grüße"
+    );
+    let back: BTreeMap<String, String> = logfmt::from_str(&line).unwrap();
+    assert_eq!(
+        back,
+        map.iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    );
+
+    let mut map = BTreeMap::new();
+    map.insert("bad key", 1);
+    assert!(logfmt::to_string(&map).is_err());
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct Request {
+    pub method: String,
+    pub tags: Vec<u32>,
+    pub db: Database,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct Database {
+    pub host: String,
+    pub port: u16,
+}
+
+#[test]
+fn test_nested_roundtrip() {
+    let config = Config::new().seq_separator(",");
+    let request = Request {
+        method: "GET".into(),
+        tags: vec![1, 2],
+        db: Database {
+            host: "localhost".into(),
+            port: 5432,
+        },
+    };
+    let line = logfmt::to_string_with_config(&request, &config).unwrap();
+    assert_eq!(line, "method=GET tags=1,2 db.host=localhost db.port=5432");
+    let back: Request = logfmt::from_str_with_config(&line, &config).unwrap();
+    assert_eq!(back, request);
+}
+
+#[test]
+fn test_errors() {
+    let input = "level=info msg=x port=http";
+    let err = logfmt::from_str::<Record>(input).unwrap_err();
+    assert_eq!(err.path(), Some("port"));
+    assert_eq!(err.span(), Some(22..26));
+    assert_eq!(
+        err.render(input).unwrap(),
+        format!("{}\n{}^^^^", input, " ".repeat(22))
+    );
+
+    let config = Config::new().seq_separator(",");
+    let input = "method=GET tags=1,x db.host=h db.port=1";
+    let err = logfmt::from_str_with_config::<Request>(input, &config).unwrap_err();
+    assert_eq!(err.path(), Some("tags[1]"));
+    assert_eq!(err.span(), Some(18..19));
+
+    let err = logfmt::from_str::<Record>("level=info msg=\"oops").unwrap_err();
+    assert!(matches!(err, Error::UnterminatedQuote { offset: 15 }));
+    assert!(logfmt::from_str::<Record>("=x").is_err());
+}
@@ -0,0 +1,278 @@
+#[macro_use]
+extern crate serde_derive;
+
+use serde_plain::openapi::{Parameter, Style};
+use serde_plain::Config;
+use std::collections::BTreeMap;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Default)]
+#[allow(non_snake_case)]
+pub struct Color {
+    pub R: u8,
+    pub G: u8,
+    pub B: u8,
+}
+
+fn color() -> Color {
+    Color {
+        R: 100,
+        G: 200,
+        B: 150,
+    }
+}
+
+fn colors() -> Vec<String> {
+    vec!["blue".into(), "black".into(), "brown".into()]
+}
+
+/// The examples of the OpenAPI specification, in the order empty,
+/// primitive, array and object.
+const EXAMPLES: &[(Style, bool, [&str; 4])] = &[
+    (
+        Style::Matrix,
+        false,
+        [
+            ";color",
+            ";color=blue",
+            ";color=blue,black,brown",
+            ";color=R,100,G,200,B,150",
+        ],
+    ),
+    (
+        Style::Matrix,
+        true,
+        [
+            ";color",
+            ";color=blue",
+            ";color=blue;color=black;color=brown",
+            ";R=100;G=200;B=150",
+        ],
+    ),
+    (
+        Style::Label,
+        false,
+        [".", ".blue", ".blue.black.brown", ".R.100.G.200.B.150"],
+    ),
+    (
+        Style::Label,
+        true,
+        [".", ".blue", ".blue.black.brown", ".R=100.G=200.B=150"],
+    ),
+    (
+        Style::Form,
+        false,
+        [
+            "color=",
+            "color=blue",
+            "color=blue,black,brown",
+            "color=R,100,G,200,B,150",
+        ],
+    ),
+    (
+        Style::Form,
+        true,
+        [
+            "color=",
+            "color=blue",
+            "color=blue&color=black&color=brown",
+            "R=100&G=200&B=150",
+        ],
+    ),
+    (
+        Style::Simple,
+        false,
+        ["", "blue", "blue,black,brown", "R,100,G,200,B,150"],
+    ),
+    (
+        Style::Simple,
+        true,
+        ["", "blue", "blue,black,brown", "R=100,G=200,B=150"],
+    ),
+];
+
+#[test]
+fn test_spec_examples() {
+    for &(style, explode, [empty, primitive, array, object]) in EXAMPLES {
+        let param = Parameter::new("color").style(style).explode(explode);
+        let context = format!("{:?} explode={}", style, explode);
+
+        assert_eq!(
+            param.to_string(&None::<String>).unwrap(),
+            empty,
+            "{}",
+            context
+        );
+        assert_eq!(param.to_string(&"blue").unwrap(), primitive, "{}", context);
+        assert_eq!(param.to_string(&colors()).unwrap(), array, "{}", context);
+        assert_eq!(param.to_string(&color()).unwrap(), object, "{}", context);
+
+        assert_eq!(
+            param.from_str::<Option<String>>(empty).unwrap(),
+            None,
+            "{}",
+            context
+        );
+        assert_eq!(
+            param.from_str::<String>(primitive).unwrap(),
+            "blue",
+            "{}",
+            context
+        );
+        assert_eq!(
+            param.from_str::<Vec<String>>(array).unwrap(),
+            colors(),
+            "{}",
+            context
+        );
+        assert_eq!(
+            param.from_str::<Color>(object).unwrap(),
+            color(),
+            "{}",
+            context
+        );
+        assert_eq!(
+            param.from_str::<Vec<String>>(empty).unwrap(),
+            Vec::<String>::new()
+        );
+    }
+}
+
+#[test]
+fn test_maps_and_tuples() {
+    let param = Parameter::new("filter").style(Style::Form).explode(true);
+    let mut map = BTreeMap::new();
+    map.insert("limit".to_string(), 10);
+    map.insert("offset".to_string(), 20);
+    assert_eq!(param.to_string(&map).unwrap(), "limit=10&offset=20");
+    assert_eq!(
+        param
+            .from_str::<BTreeMap<String, u32>>("limit=10&offset=20")
+            .unwrap(),
+        map
+    );
+
+    let param = Parameter::new("point").style(Style::Matrix);
+    assert_eq!(param.to_string(&(1, -2)).unwrap(), ";point=1,-2");
+    assert_eq!(
+        param.from_str::<(i32, i32)>(";point=1,-2").unwrap(),
+        (1, -2)
+    );
+
+    let param = Parameter::new("id");
+    assert_eq!(param.from_str::<u64>("5").unwrap(), 5);
+    assert_eq!(param.from_str::<Option<u64>>("").unwrap(), None);
+
+    let config = Config::new().seq_separator("|");
+    let param = Parameter::new("flags").style(Style::Form).explode(true);
+    let value: Vec<Vec<u8>> = param
+        .from_str_with_config("flags=1|2&flags=3", &config)
+        .unwrap();
+    assert_eq!(value, vec![vec![1, 2], vec![3]]);
+}
+
+#[test]
+fn test_errors() {
+    let param = Parameter::new("id").style(Style::Matrix);
+    let err = param.from_str::<Vec<u32>>(";id=1,x,3").unwrap_err();
+    assert_eq!(err.path(), Some("[1]"));
+    assert_eq!(err.span(), Some(6..7));
+
+    let err = param.from_str::<u32>(";other=1").unwrap_err();
+    assert_eq!(err.to_string(), "expected `;id` for parameter `id`");
+
+    let param = Parameter::new("color").style(Style::Form).explode(true);
+    let err = param.from_str::<Color>("R=100&G=x&B=1").unwrap_err();
+    assert_eq!(err.path(), Some("G"));
+    assert_eq!(err.span(), Some(8..9));
+    assert!(param.from_str::<Vec<String>>("color=a&other=b").is_err());
+
+    let param = Parameter::new("color");
+    assert!(param.from_str::<Color>("R,100,G").is_err());
+}
+
+#[test]
+fn test_empty_and_repeated_keys() {
+    let form = Parameter::new("id").style(Style::Form).explode(true);
+    assert_eq!(
+        form.from_str::<BTreeMap<String, String>>("&&")
+            .unwrap_err()
+            .to_string(),
+        "expected a name for every property for parameter `id`"
+    );
+    let err = form
+        .from_str::<BTreeMap<String, String>>("id=1&&id=2")
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "expected a name for every property for parameter `id`"
+    );
+    let err = form
+        .from_str::<BTreeMap<String, String>>("id=1&id=2")
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "duplicate property `id` at `id` (offset 5)"
+    );
+    assert_eq!(err.span(), Some(5..7));
+
+    let matrix = Parameter::new("id").style(Style::Matrix).explode(true);
+    assert!(matrix.from_str::<BTreeMap<String, u32>>(";=1").is_err());
+    assert!(Parameter::new("m")
+        .from_str::<BTreeMap<String, u32>>("a,1,a,2")
+        .is_err());
+
+    let mut map = BTreeMap::new();
+    map.insert("", "x");
+    assert!(form.to_string(&map).is_err());
+    assert!(Parameter::new("m").to_string(&map).is_err());
+}
+
+#[test]
+fn test_single_empty_item() {
+    for style in &[Style::Simple, Style::Label, Style::Matrix, Style::Form] {
+        for explode in &[false, true] {
+            let param = Parameter::new("id").style(*style).explode(*explode);
+            assert_eq!(
+                param.to_string(&vec![""]).unwrap_err().to_string(),
+                "cannot represent a sequence with a single empty element"
+            );
+            let s = param.to_string(&vec!["", ""]).unwrap();
+            assert_eq!(
+                param.from_str::<Vec<String>>(&s).unwrap(),
+                vec!["".to_string(), "".to_string()]
+            );
+        }
+    }
+}
+
+#[test]
+fn test_delimiters_in_values() {
+    let err = Parameter::new("id").to_string(&vec!["a,b"]).unwrap_err();
+    assert_eq!(err.to_string(), "`a,b` contains the delimiter `,`");
+    assert_eq!(Parameter::new("id").to_string(&"a,b").unwrap(), "a,b");
+
+    let label = Parameter::new("v").style(Style::Label);
+    assert!(label.to_string(&vec![1.5]).is_err());
+    assert_eq!(label.to_string(&vec![1, 2]).unwrap(), ".1.2");
+
+    let matrix = Parameter::new("id").style(Style::Matrix).explode(true);
+    assert!(matrix.to_string(&vec!["a;b"]).is_err());
+    assert_eq!(matrix.to_string(&vec!["a,b"]).unwrap(), ";id=a,b");
+
+    let form = Parameter::new("id").style(Style::Form).explode(true);
+    assert!(form.to_string(&vec!["a&b"]).is_err());
+
+    let mut map = BTreeMap::new();
+    map.insert("k=1", "v");
+    assert!(form.to_string(&map).is_err());
+    assert!(Parameter::new("m").to_string(&map).is_ok());
+
+    let mut map = BTreeMap::new();
+    map.insert("k", "a=b&c");
+    assert!(form.to_string(&map).is_err());
+    let mut map = BTreeMap::new();
+    map.insert("k".to_string(), "a=b".to_string());
+    let s = form.to_string(&map).unwrap();
+    assert_eq!(s, "k=a=b");
+    assert_eq!(form.from_str::<BTreeMap<String, String>>(&s).unwrap(), map);
+}
@@ -0,0 +1,105 @@
+#[macro_use]
+extern crate serde_derive;
+
+use serde_plain::Config;
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct Version {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct Endpoint {
+    pub host: String,
+    pub port: u16,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct Size(pub u32, pub u32);
+
+fn config() -> Config {
+    Config::new()
+        .pattern("Version", "v{major}.{minor}.{patch}")
+        .unwrap()
+        .pattern("Endpoint", "{host}:{port}")
+        .unwrap()
+        .pattern("Size", "{0}x{1}")
+        .unwrap()
+}
+
+#[test]
+fn test_struct_pattern_roundtrip() {
+    let config = config();
+    let version = Version {
+        major: 1,
+        minor: 22,
+        patch: 3,
+    };
+    assert_eq!(
+        serde_plain::to_string_with_config(&version, &config).unwrap(),
+        "v1.22.3"
+    );
+    assert_eq!(
+        serde_plain::from_str_with_config::<Version>("v1.22.3", &config).unwrap(),
+        version
+    );
+
+    let endpoint = Endpoint {
+        host: "localhost".into(),
+        port: 8080,
+    };
+    assert_eq!(
+        serde_plain::to_string_with_config(&endpoint, &config).unwrap(),
+        "localhost:8080"
+    );
+    assert_eq!(
+        serde_plain::from_str_with_config::<Endpoint>("localhost:8080", &config).unwrap(),
+        endpoint
+    );
+}
+
+#[test]
+fn test_tuple_struct_pattern_roundtrip() {
+    let config = config();
+    assert_eq!(
+        serde_plain::to_string_with_config(&Size(800, 600), &config).unwrap(),
+        "800x600"
+    );
+    assert_eq!(
+        serde_plain::from_str_with_config::<Size>("800x600", &config).unwrap(),
+        Size(800, 600)
+    );
+    assert!(serde_plain::to_string(&Size(800, 600)).is_err());
+}
+
+#[test]
+fn test_pattern_errors() {
+    let config = config();
+    let err = serde_plain::from_str_with_config::<Version>("v1.x.3", &config).unwrap_err();
+    assert_eq!(err.path(), Some("minor"));
+    assert_eq!(err.span(), Some(3..4));
+
+    let err = serde_plain::from_str_with_config::<Version>("1.2.3", &config).unwrap_err();
+    assert_eq!(err.to_string(), "expected `v` at offset 0");
+    let err = serde_plain::from_str_with_config::<Version>("v1.2", &config).unwrap_err();
+    assert_eq!(err.to_string(), "expected `.` after placeholder `{minor}`");
+
+    let endpoint = Endpoint {
+        host: "::1".into(),
+        port: 80,
+    };
+    assert!(serde_plain::to_string_with_config(&endpoint, &config).is_err());
+}
+
+#[test]
+fn test_invalid_pattern() {
+    let err = Config::new().pattern("Size", "{0}{1}").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "invalid pattern `{0}{1}`: placeholders must be separated by text"
+    );
+    assert!(Config::new().pattern("Size", "{0").is_err());
+    assert!(Config::new().pattern("Size", "{0}x}").is_err());
+}
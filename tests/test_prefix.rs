@@ -0,0 +1,146 @@
+#[macro_use]
+extern crate serde_derive;
+
+use serde_plain::{Config, ParseMode, Quoting};
+
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Unit {
+    M,
+    Ms,
+    S,
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+pub struct Port(u16);
+
+#[test]
+fn test_prefix_numbers() {
+    assert_eq!(
+        serde_plain::from_str_prefix::<u32>("42rest").unwrap(),
+        (42, "rest")
+    );
+    assert_eq!(
+        serde_plain::from_str_prefix::<i8>("-12-3").unwrap(),
+        (-12, "-3")
+    );
+    assert_eq!(
+        serde_plain::from_str_prefix::<f64>("1.5e3x").unwrap(),
+        (1500.0, "x")
+    );
+    assert_eq!(
+        serde_plain::from_str_prefix::<f64>("2.5em").unwrap(),
+        (2.5, "em")
+    );
+    assert_eq!(
+        serde_plain::from_str_prefix::<Port>("8080/tcp").unwrap(),
+        (Port(8080), "/tcp")
+    );
+    assert!(serde_plain::from_str_prefix::<u32>("x42").is_err());
+    assert!(serde_plain::from_str_prefix::<u8>("300").is_err());
+}
+
+#[test]
+fn test_prefix_special_floats() {
+    let prefix = |s| serde_plain::from_str_prefix::<f64>(s).unwrap();
+    for (input, rest) in &[
+        ("inf,", ","),
+        ("Infinity)", ")"),
+        ("-INF rest", " rest"),
+        ("+infinityx", "x"),
+        ("infinit", "init"),
+    ] {
+        let (value, remaining) = prefix(input);
+        assert!(value.is_infinite(), "{}", input);
+        assert_eq!(remaining, *rest, "{}", input);
+    }
+    assert_eq!(prefix("-inf").0, f64::NEG_INFINITY);
+    for input in &["nan;", "NaN;", "NAN;", "-nan;"] {
+        let (value, rest) = prefix(input);
+        assert!(value.is_nan(), "{}", input);
+        assert_eq!(rest, ";");
+    }
+}
+
+#[test]
+fn test_prefix_bool_char_variant() {
+    assert_eq!(
+        serde_plain::from_str_prefix::<bool>("true,false").unwrap(),
+        (true, ",false")
+    );
+    assert_eq!(
+        serde_plain::from_str_prefix::<char>("äb").unwrap(),
+        ('ä', "b")
+    );
+    assert_eq!(
+        serde_plain::from_str_prefix::<Unit>("ms ago").unwrap(),
+        (Unit::Ms, " ago")
+    );
+    assert_eq!(
+        serde_plain::from_str_prefix::<Unit>("m2").unwrap(),
+        (Unit::M, "2")
+    );
+    let err = serde_plain::from_str_prefix::<Unit>("hours").unwrap_err();
+    assert!(err.to_string().contains("hours"), "{}", err);
+}
+
+#[test]
+fn test_prefix_string_consumes_everything() {
+    assert_eq!(
+        serde_plain::from_str_prefix::<String>("a b").unwrap(),
+        ("a b".to_string(), "")
+    );
+    assert_eq!(
+        serde_plain::from_str_prefix::<Option<u32>>("").unwrap(),
+        (None, "")
+    );
+    assert_eq!(
+        serde_plain::from_str_prefix::<Option<u32>>("5s").unwrap(),
+        (Some(5), "s")
+    );
+}
+
+#[test]
+fn test_until_terminator() {
+    assert_eq!(
+        serde_plain::from_str_until::<&str>("key=value", "=").unwrap(),
+        ("key", "=value")
+    );
+    assert_eq!(
+        serde_plain::from_str_until::<u32>("12;13", ";").unwrap(),
+        (12, ";13")
+    );
+    assert_eq!(
+        serde_plain::from_str_until::<u32>("12", ";").unwrap(),
+        (12, "")
+    );
+    assert!(serde_plain::from_str_until::<u32>("12x;13", ";").is_err());
+
+    let config = Config::new().quoting(Quoting::DoubleQuotes);
+    assert_eq!(
+        serde_plain::from_str_until_with_config::<String>(r#""a;b";c"#, ";", &config).unwrap(),
+        ("a;b".to_string(), ";c")
+    );
+}
+
+#[test]
+fn test_prefix_trimmed() {
+    let trimmed = Config::new().parse_mode(ParseMode::Trimmed);
+    assert_eq!(
+        serde_plain::from_str_prefix_with_config::<u32>(" 42rest", &trimmed).unwrap(),
+        (42, "rest")
+    );
+    assert_eq!(
+        serde_plain::from_str_prefix_with_config::<f64>("\t-1.5 s", &trimmed).unwrap(),
+        (-1.5, " s")
+    );
+    assert_eq!(
+        serde_plain::from_str_prefix_with_config::<Unit>("  ms", &trimmed).unwrap(),
+        (Unit::Ms, "")
+    );
+    assert_eq!(
+        serde_plain::from_str_prefix_with_config::<Option<u32>>("  ", &trimmed).unwrap(),
+        (None, "")
+    );
+    assert!(serde_plain::from_str_prefix::<u32>(" 42rest").is_err());
+}
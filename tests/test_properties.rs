@@ -0,0 +1,158 @@
+#[macro_use]
+extern crate serde_derive;
+
+use serde_plain::properties;
+use serde_plain::{Config, Error};
+use std::collections::BTreeMap;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Level {
+    Debug,
+    Info,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct Database {
+    pub host: String,
+    pub port: u16,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct Settings {
+    pub title: String,
+    pub level: Level,
+    pub workers: Option<u32>,
+    pub db: Database,
+}
+
+#[test]
+fn test_from_str() {
+    let input = "\
+# comment
+! another comment
+
+title = Hello \\
+        World
+level:info
+   db.host    localhost
+db.port=1
+db.port = 5432
+";
+    let settings: Settings = properties::from_str(input).unwrap();
+    assert_eq!(
+        settings,
+        Settings {
+            title: "Hello World".into(),
+            level: Level::Info,
+            workers: None,
+            db: Database {
+                host: "localhost".into(),
+                port: 5432,
+            },
+        }
+    );
+}
+
+#[test]
+fn test_escapes() {
+    let input = "key\\ with\\=odd\\:chars = \\ lead\\ttab\\u00e9\\uD83D\\uDE00\\\\\r\nother=\\\r\n";
+    let map: BTreeMap<String, String> = properties::from_str(input).unwrap();
+    assert_eq!(map["key with=odd:chars"], " lead\ttab\u{e9}\u{1f600}\\");
+    assert_eq!(map["other"], "");
+
+    let out = properties::to_string(&map).unwrap();
+    assert_eq!(
+        out,
+        "key\\ with\\=odd\\:chars=\\ lead\\ttab\u{e9}\u{1f600}\\\\\nother=\n"
+    );
+    let back: BTreeMap<String, String> = properties::from_str(&out).unwrap();
+    assert_eq!(back, map);
+
+    let err = properties::from_str::<BTreeMap<String, String>>("a = \\u12g4").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "invalid unicode escape `\\u12g4` for key `a` at line 1, column 5"
+    );
+}
+
+#[test]
+fn test_roundtrip() {
+    let settings = Settings {
+        title: "multi\nline".into(),
+        level: Level::Debug,
+        workers: Some(4),
+        db: Database {
+            host: "db".into(),
+            port: 1,
+        },
+    };
+    let out = properties::to_string(&settings).unwrap();
+    assert_eq!(
+        out,
+        "title=multi\\nline\nlevel=debug\nworkers=4\ndb.host=db\ndb.port=1\n"
+    );
+    let back: Settings = properties::from_str(&out).unwrap();
+    assert_eq!(back, settings);
+
+    let config = Config::new().pattern("Database", "{host}:{port}").unwrap();
+    let out = properties::to_string_with_config(&settings, &config).unwrap();
+    assert!(out.ends_with("db=db:1\n"));
+    let back: Settings = properties::from_str_with_config(&out, &config).unwrap();
+    assert_eq!(back, settings);
+
+    assert!(properties::to_string(&42).is_err());
+}
+
+#[test]
+fn test_errors_have_positions() {
+    let input = "title = x\nlevel = info\n\ndb.host = h\n  db.port =  http\n";
+    let err = properties::from_str::<Settings>(input).unwrap_err();
+    assert!(matches!(err, Error::Position { .. }));
+    assert_eq!(err.line(), Some(5));
+    assert_eq!(err.column(), Some(14));
+    assert_eq!(err.path(), Some("db.port"));
+    assert_eq!(
+        err.to_string(),
+        "cannot parse u16: invalid digit found in string for key `db.port` at line 5, column 14"
+    );
+
+    let input = "title = x\nlevel = \\\n  warn\ndb.host = h\ndb.port = 1\n";
+    let err = properties::from_str::<Settings>(input).unwrap_err();
+    assert_eq!((err.line(), err.column()), (Some(3), Some(3)));
+
+    let err =
+        properties::from_str::<Settings>("level = info\ndb.host = h\ndb.port = 1").unwrap_err();
+    assert_eq!(err.to_string(), "missing field `title` for key `title`");
+    assert_eq!((err.line(), err.column()), (None, None));
+    match err {
+        Error::Position {
+            key,
+            line: None,
+            column: None,
+            error,
+        } => {
+            assert_eq!(key, "title");
+            assert!(matches!(*error, Error::MissingField("title")));
+        }
+        other => panic!("unexpected error {:?}", other),
+    }
+}
+
+#[test]
+fn test_empty_key() {
+    for input in &["=oops\n", ": oops\n"] {
+        let input = format!(
+            "title = x\nlevel = info\ndb.host = h\ndb.port = 1\n{}",
+            input
+        );
+        let settings: Settings = properties::from_str(&input).unwrap();
+        assert_eq!(settings.title, "x");
+        assert_eq!(settings.db.port, 1);
+
+        let map: BTreeMap<String, String> = properties::from_str(&input).unwrap();
+        assert_eq!(map[""], "oops");
+        assert_eq!(map["db.port"], "1");
+        assert_eq!(map.len(), 5);
+    }
+}
@@ -0,0 +1,213 @@
+#[macro_use]
+extern crate serde_derive;
+
+use serde_plain::{Config, Error, Quoting};
+use std::collections::BTreeMap;
+
+fn quoted() -> Config {
+    Config::new()
+        .seq_separator(",")
+        .quoting(Quoting::DoubleQuotes)
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug)]
+pub struct Label {
+    name: String,
+    value: String,
+}
+
+#[test]
+fn test_quoted_seq() {
+    let config = quoted();
+    let items = vec!["a,b".to_string(), "c".to_string()];
+    assert_eq!(
+        serde_plain::to_string_with_config(&items, &config).unwrap(),
+        r#""a,b",c"#
+    );
+    assert_eq!(
+        serde_plain::from_str_with_config::<Vec<String>>(r#""a,b",c"#, &config).unwrap(),
+        items
+    );
+    assert_eq!(
+        serde_plain::from_str_with_config::<Vec<&str>>(r#""a,b",c"#, &config).unwrap(),
+        vec!["a,b", "c"]
+    );
+
+    assert_eq!(
+        serde_plain::to_string_with_config(&vec![""], &config).unwrap(),
+        r#""""#
+    );
+    assert_eq!(
+        serde_plain::from_str_with_config::<Vec<String>>(r#""""#, &config).unwrap(),
+        vec!["".to_string()]
+    );
+    assert!(
+        serde_plain::from_str_with_config::<Vec<String>>("", &config)
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn test_escapes() {
+    let config = quoted();
+    let value = "say \"hi\"\n\\ \u{1}".to_string();
+    let text = serde_plain::to_string_with_config(&value, &config).unwrap();
+    assert_eq!(text, r#""say \"hi\"\n\\ \u{1}""#);
+    assert_eq!(
+        serde_plain::from_str_with_config::<String>(&text, &config).unwrap(),
+        value
+    );
+    assert_eq!(
+        serde_plain::from_str_with_config::<String>(r#""\u{1F600}\t""#, &config).unwrap(),
+        "\u{1F600}\t"
+    );
+    assert!(serde_plain::from_str_with_config::<String>(r#""\q""#, &config).is_err());
+    assert!(serde_plain::from_str_with_config::<String>(r#""\u{zz}""#, &config).is_err());
+    assert!(serde_plain::from_str_with_config::<String>(r#""a"b"#, &config).is_err());
+
+    assert_eq!(
+        serde_plain::to_string_with_config(&"plain", &config).unwrap(),
+        "plain"
+    );
+    assert_eq!(
+        serde_plain::to_string_with_config(&" padded", &config).unwrap(),
+        "\" padded\""
+    );
+    assert_eq!(
+        serde_plain::from_str_with_config::<u32>(r#""42""#, &config).unwrap(),
+        42
+    );
+}
+
+#[test]
+fn test_quoted_option() {
+    let config = quoted();
+    assert_eq!(
+        serde_plain::to_string_with_config(&Some(""), &config).unwrap(),
+        r#""""#
+    );
+    assert_eq!(
+        serde_plain::from_str_with_config::<Option<String>>(r#""""#, &config).unwrap(),
+        Some("".to_string())
+    );
+    assert_eq!(
+        serde_plain::from_str_with_config::<Option<String>>("", &config).unwrap(),
+        None
+    );
+}
+
+#[test]
+fn test_quoted_map_and_struct() {
+    let config = quoted();
+    let mut map = BTreeMap::new();
+    map.insert("a".to_string(), "x,y".to_string());
+    map.insert("b=c".to_string(), "z".to_string());
+    let text = serde_plain::to_string_with_config(&map, &config).unwrap();
+    assert_eq!(text, r#"a="x,y","b=c"=z"#);
+    assert_eq!(
+        serde_plain::from_str_with_config::<BTreeMap<String, String>>(&text, &config).unwrap(),
+        map
+    );
+
+    let label = Label {
+        name: "env".into(),
+        value: "a \"b\", c".into(),
+    };
+    let text = serde_plain::to_string_with_config(&label, &config).unwrap();
+    assert_eq!(text, r#"name=env,value="a \"b\", c""#);
+    assert_eq!(
+        serde_plain::from_str_with_config::<Label>(&text, &config).unwrap(),
+        label
+    );
+    assert_eq!(
+        serde_plain::from_str_with_config::<Label>(r#"value="=",name=x"#, &config).unwrap(),
+        Label {
+            name: "x".into(),
+            value: "=".into()
+        }
+    );
+}
+
+#[test]
+fn test_unquoted_separator() {
+    let config = Config::new().seq_separator(",");
+    assert!(serde_plain::to_string_with_config(&vec!["a,b"], &config).is_err());
+    assert_eq!(
+        serde_plain::from_str_with_config::<Vec<String>>(r#""a,b""#, &config).unwrap(),
+        vec!["\"a".to_string(), "b\"".to_string()]
+    );
+}
+
+#[test]
+fn test_unterminated_quote() {
+    let config = quoted();
+    let offset = |s| match serde_plain::from_str_with_config::<Vec<String>>(s, &config) {
+        Err(Error::UnterminatedQuote { offset }) => offset,
+        other => panic!("unexpected result {:?}", other),
+    };
+    assert_eq!(offset(r#""abc,d"#), 0);
+    assert_eq!(offset(r#"a,"b"#), 2);
+    assert_eq!(offset(r#"a,"b\"c"#), 2);
+    match serde_plain::from_str_with_config::<Label>(r#"name=x,value="v"#, &config) {
+        Err(Error::UnterminatedQuote { offset }) => assert_eq!(offset, 13),
+        other => panic!("unexpected result {:?}", other),
+    }
+    assert_eq!(
+        serde_plain::from_str_with_config::<String>(r#""abc"#, &config)
+            .unwrap_err()
+            .to_string(),
+        "unterminated quote at offset 0"
+    );
+}
+
+fn shell() -> Config {
+    Config::new().quoting(Quoting::Shell)
+}
+
+#[test]
+fn test_shell_words() {
+    let config = shell();
+    let words = vec!["echo", "it's here", "", "a=b", "$HOME", "ok-1.2/x"];
+    let s = serde_plain::to_string_with_config(&words, &config).unwrap();
+    assert_eq!(s, r#"echo 'it'\''s here' '' 'a=b' '$HOME' ok-1.2/x"#);
+    let back: Vec<String> = serde_plain::from_str_with_config(&s, &config).unwrap();
+    assert_eq!(back, words);
+
+    let words: Vec<String> =
+        serde_plain::from_str_with_config("  a\\ b   \"x \\\"y\\\" \\$z \\n\"  c'd'e ", &config)
+            .unwrap();
+    assert_eq!(words, vec!["a b", "x \"y\" $z \\n", "cde"]);
+
+    let value: String = serde_plain::from_str_with_config(r#"'a b'"c"\d"#, &config).unwrap();
+    assert_eq!(value, "a bcd");
+    let value: u32 = serde_plain::from_str_with_config("'42'", &config).unwrap();
+    assert_eq!(value, 42);
+    assert!(serde_plain::from_str_with_config::<String>("a b", &config).is_err());
+
+    let empty: Vec<String> = serde_plain::from_str_with_config("   ", &config).unwrap();
+    assert!(empty.is_empty());
+}
+
+#[test]
+fn test_shell_separator() {
+    let config = shell().seq_separator(",");
+    let values = vec!["a b", "c,d"];
+    let s = serde_plain::to_string_with_config(&values, &config).unwrap();
+    assert_eq!(s, "'a b','c,d'");
+    let back: Vec<String> = serde_plain::from_str_with_config(&s, &config).unwrap();
+    assert_eq!(back, values);
+
+    // the space is only the default while shell quoting is in effect.
+    let config = shell().quoting(Quoting::DoubleQuotes);
+    assert!(serde_plain::to_string_with_config(&values, &config).is_err());
+
+    match serde_plain::from_str_with_config::<Vec<String>>("a 'b", &shell()) {
+        Err(Error::UnterminatedQuote { offset }) => assert_eq!(offset, 2),
+        other => panic!("unexpected result {:?}", other),
+    }
+    match serde_plain::from_str_with_config::<String>("\"ab", &shell()) {
+        Err(Error::UnterminatedQuote { offset }) => assert_eq!(offset, 0),
+        other => panic!("unexpected result {:?}", other),
+    }
+}
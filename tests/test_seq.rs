@@ -0,0 +1,247 @@
+#[macro_use]
+extern crate serde_derive;
+
+use serde_plain::{Config, ParseMode, Quoting};
+use std::collections::{BTreeSet, HashSet};
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Perm {
+    Read,
+    Write,
+    Exec,
+}
+
+fn flags() -> Config {
+    Config::new().seq_separator("|")
+}
+
+#[test]
+fn test_seq_unsupported_by_default() {
+    assert!(serde_plain::to_string(&vec![Perm::Read]).is_err());
+    assert!(serde_plain::from_str::<Vec<Perm>>("read").is_err());
+}
+
+#[test]
+fn test_flags_roundtrip() {
+    let config = flags();
+    let perms = vec![Perm::Read, Perm::Write, Perm::Exec];
+    assert_eq!(
+        serde_plain::to_string_with_config(&perms, &config).unwrap(),
+        "read|write|exec"
+    );
+    assert_eq!(
+        serde_plain::from_str_with_config::<Vec<Perm>>("read|write|exec", &config).unwrap(),
+        perms
+    );
+
+    let set: BTreeSet<Perm> = serde_plain::from_str_with_config("exec|read", &config).unwrap();
+    assert_eq!(set, vec![Perm::Read, Perm::Exec].into_iter().collect());
+    assert_eq!(
+        serde_plain::to_string_with_config(&set, &config).unwrap(),
+        "read|exec"
+    );
+
+    assert_eq!(
+        serde_plain::to_string_with_config(&Vec::<Perm>::new(), &config).unwrap(),
+        ""
+    );
+    assert!(serde_plain::from_str_with_config::<Vec<Perm>>("", &config)
+        .unwrap()
+        .is_empty());
+    assert!(serde_plain::from_str_with_config::<Vec<Perm>>("read|", &config).is_err());
+}
+
+#[test]
+fn test_seq_separator() {
+    let config = Config::new().seq_separator(", ");
+    assert_eq!(
+        serde_plain::to_string_with_config(&vec![1, 2, 3], &config).unwrap(),
+        "1, 2, 3"
+    );
+    assert_eq!(
+        serde_plain::from_str_with_config::<Vec<u32>>("1, 2, 3", &config).unwrap(),
+        vec![1, 2, 3]
+    );
+    assert!(serde_plain::to_string_with_config(&vec!["a, b"], &config).is_err());
+    assert!(serde_plain::to_string_with_config(&vec![""], &config).is_err());
+    assert_eq!(
+        serde_plain::to_string_with_config(&vec!["", ""], &config).unwrap(),
+        ", "
+    );
+
+    let trimmed = Config::new()
+        .seq_separator("|")
+        .parse_mode(ParseMode::Trimmed);
+    assert_eq!(
+        serde_plain::from_str_with_config::<Vec<Perm>>("read | exec", &trimmed).unwrap(),
+        vec![Perm::Read, Perm::Exec]
+    );
+}
+
+#[test]
+fn test_seq_unique() {
+    let config = flags().seq_unique(true);
+    assert_eq!(
+        serde_plain::from_str_with_config::<Vec<Perm>>("read|read", &config)
+            .unwrap_err()
+            .to_string(),
+        "duplicate element `read` at `[1]` (offset 5)"
+    );
+    assert!(serde_plain::to_string_with_config(&vec![Perm::Exec, Perm::Exec], &config).is_err());
+    assert_eq!(
+        serde_plain::from_str_with_config::<Vec<Perm>>("read|read", &flags()).unwrap(),
+        vec![Perm::Read, Perm::Read]
+    );
+}
+
+#[test]
+fn test_seq_unique_compares_values() {
+    let config = flags().seq_unique(true);
+    assert_eq!(
+        serde_plain::from_str_with_config::<Vec<u32>>("1|01", &config)
+            .unwrap_err()
+            .to_string(),
+        "duplicate element `01` at `[1]` (offset 2)"
+    );
+    assert!(serde_plain::from_str_with_config::<Vec<f64>>("1|1.0", &config).is_err());
+    assert!(serde_plain::from_str_with_config::<Vec<String>>(
+        "a|\"a\"",
+        &config.clone().quoting(Quoting::DoubleQuotes)
+    )
+    .is_err());
+    assert!(serde_plain::from_str_with_config::<Vec<Perm>>(
+        "read|re",
+        &config.clone().variant_prefixes(true)
+    )
+    .is_err());
+    assert!(serde_plain::from_str_with_config::<Vec<Perm>>(
+        "0|read",
+        &config.clone().accept_variant_index(true)
+    )
+    .is_err());
+    assert_eq!(
+        serde_plain::from_str_with_config::<Vec<String>>("01|1", &config).unwrap(),
+        vec!["01".to_string(), "1".to_string()]
+    );
+}
+
+#[test]
+fn test_seq_sorted() {
+    let config = flags().seq_sorted(true);
+    let set: HashSet<Perm> = vec![Perm::Exec, Perm::Read, Perm::Write]
+        .into_iter()
+        .collect();
+    assert_eq!(
+        serde_plain::to_string_with_config(&set, &config).unwrap(),
+        "read|write|exec"
+    );
+    assert_eq!(
+        serde_plain::to_string_with_config(&vec![10, 9, 100], &config).unwrap(),
+        "9|10|100"
+    );
+    assert_eq!(
+        serde_plain::to_string_with_config(&vec!["b", "c", "a"], &config).unwrap(),
+        "a|b|c"
+    );
+    assert_eq!(
+        serde_plain::to_string_with_config(&vec!["9", "10", "100"], &config).unwrap(),
+        "10|100|9"
+    );
+    assert_eq!(
+        serde_plain::to_string_with_config(&vec![9.5, 10.5, -1.0], &config).unwrap(),
+        "-1|9.5|10.5"
+    );
+    assert_eq!(
+        serde_plain::to_string_with_config(&vec![Some(3u8), None, Some(1)], &config).unwrap(),
+        "1|3|"
+    );
+}
+
+/// Mimics the serde support of `bitflags`, which uses its own text format.
+#[derive(Debug, PartialEq)]
+pub struct PermFlags(u8);
+
+impl serde::Serialize for PermFlags {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let names: Vec<_> = ["READ", "WRITE", "EXEC"]
+            .iter()
+            .enumerate()
+            .filter(|(bit, _)| self.0 & 1 << bit != 0)
+            .map(|(_, name)| *name)
+            .collect();
+        serializer.collect_str(&names.join(" | "))
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for PermFlags {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = <&str>::deserialize(deserializer)?;
+        let mut bits = 0;
+        for name in s.split('|').map(str::trim).filter(|name| !name.is_empty()) {
+            match ["READ", "WRITE", "EXEC"].iter().position(|x| *x == name) {
+                Some(bit) => bits |= 1 << bit,
+                None => return Err(serde::de::Error::custom("unknown flag")),
+            }
+        }
+        Ok(PermFlags(bits))
+    }
+}
+
+#[test]
+fn test_bitflags_keep_their_format() {
+    let config = flags().seq_sorted(true);
+    let flags = PermFlags(0b101);
+    let s = serde_plain::to_string_with_config(&flags, &config).unwrap();
+    assert_eq!(s, "READ | EXEC");
+    assert_eq!(
+        serde_plain::from_str_with_config::<PermFlags>(&s, &config).unwrap(),
+        flags
+    );
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct Point(i32, i32);
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct Rgb(u8, u8, u8);
+
+#[test]
+fn test_tuple_struct_roundtrip() {
+    let config = Config::new().tuple_separator(",");
+    assert_eq!(
+        serde_plain::to_string_with_config(&Point(1, -2), &config).unwrap(),
+        "1,-2"
+    );
+    assert_eq!(
+        serde_plain::from_str_with_config::<Point>("1,-2", &config).unwrap(),
+        Point(1, -2)
+    );
+    assert_eq!(
+        serde_plain::to_string_with_config(&Rgb(10, 20, 30), &config).unwrap(),
+        "10,20,30"
+    );
+    assert_eq!(
+        serde_plain::from_str_with_config::<Rgb>("10,20,30", &config).unwrap(),
+        Rgb(10, 20, 30)
+    );
+    assert_eq!(
+        serde_plain::from_str_with_config::<(u8, String)>("1,x", &config).unwrap(),
+        (1, "x".to_string())
+    );
+    assert!(serde_plain::to_string(&Point(1, 2)).is_err());
+    assert!(serde_plain::from_str::<Point>("1,2").is_err());
+}
+
+#[test]
+fn test_tuple_struct_arity() {
+    let config = Config::new().tuple_separator(",");
+    let err = serde_plain::from_str_with_config::<Rgb>("10,20", &config).unwrap_err();
+    assert_eq!(err.to_string(), "expected 3 fields, found 2");
+    let err = serde_plain::from_str_with_config::<Point>("1,2,3", &config).unwrap_err();
+    assert_eq!(err.to_string(), "expected 2 fields, found 3");
+
+    let err = serde_plain::from_str_with_config::<Rgb>("10,x,30", &config).unwrap_err();
+    assert_eq!(err.path(), Some("[1]"));
+    assert!(serde_plain::to_string_with_config(&("a,b", 1), &config).is_err());
+}
@@ -1,6 +1,7 @@
 #[macro_use]
 extern crate serde_derive;
 
+use serde_plain::{Config, FloatFormat};
 use std::fmt;
 
 #[derive(Serialize)]
@@ -48,3 +49,138 @@ fn test_to_string() {
     assert_eq!(Test::FooBarBaz.to_string(), "foo_bar_baz");
     assert_eq!(NewInt(42).to_string(), "42");
 }
+
+#[test]
+fn test_float_format() {
+    let fmt = |v: f64, format: FloatFormat| {
+        serde_plain::to_string_with_config(&v, &Config::new().float_format(format)).unwrap()
+    };
+    assert_eq!(serde_plain::to_string(&1.0f64).unwrap(), "1");
+    assert_eq!(fmt(1.0, FloatFormat::Shortest), "1");
+    assert_eq!(fmt(1.0, FloatFormat::Decimal), "1.0");
+    assert_eq!(fmt(0.25, FloatFormat::Decimal), "0.25");
+    assert_eq!(fmt(0.125, FloatFormat::Fixed(2)), "0.12");
+    assert_eq!(fmt(3.0, FloatFormat::Fixed(3)), "3.000");
+    assert_eq!(fmt(1500.0, FloatFormat::Scientific), "1.5e3");
+    assert_eq!(
+        serde_plain::to_string_with_config(
+            &2.5f32,
+            &Config::new().float_format(FloatFormat::Fixed(1))
+        )
+        .unwrap(),
+        "2.5"
+    );
+}
+
+#[test]
+fn test_float_non_finite() {
+    assert_eq!(serde_plain::to_string(&f64::NAN).unwrap(), "NaN");
+    assert_eq!(serde_plain::to_string(&f64::NEG_INFINITY).unwrap(), "-inf");
+
+    let config = Config::new()
+        .nan_repr("nan")
+        .infinity_repr("Infinity")
+        .neg_infinity_repr("-Infinity");
+    assert_eq!(
+        serde_plain::to_string_with_config(&f64::NAN, &config).unwrap(),
+        "nan"
+    );
+    assert_eq!(
+        serde_plain::to_string_with_config(&f32::INFINITY, &config).unwrap(),
+        "Infinity"
+    );
+    assert_eq!(
+        serde_plain::to_string_with_config(&f64::NEG_INFINITY, &config).unwrap(),
+        "-Infinity"
+    );
+
+    let config = Config::new().allow_non_finite(false);
+    assert!(serde_plain::to_string_with_config(&f64::INFINITY, &config).is_err());
+    assert_eq!(
+        serde_plain::to_string_with_config(&1.5f64, &config).unwrap(),
+        "1.5"
+    );
+}
+
+#[test]
+fn test_none_repr() {
+    let config = Config::new().none_repr("-");
+    let ser = |v: &Option<Option<&str>>| serde_plain::to_string_with_config(v, &config).unwrap();
+    assert_eq!(ser(&None), "-");
+    assert_eq!(ser(&Some(None)), "--");
+    assert_eq!(ser(&Some(Some(""))), "");
+    assert_eq!(ser(&Some(Some("-"))), "---");
+    assert_eq!(ser(&Some(Some("x"))), "x");
+    assert_eq!(
+        serde_plain::to_string_with_config(&(), &config).unwrap(),
+        "-"
+    );
+    assert_eq!(
+        serde_plain::to_string_with_config(&Some("null"), &Config::new().none_repr("null"))
+            .unwrap(),
+        "nullnull"
+    );
+}
+
+#[derive(Serialize)]
+pub struct Auto;
+
+#[derive(Serialize)]
+#[serde(untagged)]
+pub enum Width {
+    Auto(Auto),
+    Fixed(u32),
+}
+
+#[test]
+fn test_unit_struct() {
+    assert_eq!(serde_plain::to_string(&Auto).unwrap(), "");
+    assert_eq!(serde_plain::to_string(&Width::Auto(Auto)).unwrap(), "");
+    assert_eq!(serde_plain::to_string(&Width::Fixed(80)).unwrap(), "80");
+    assert_eq!(
+        serde_plain::to_string_with_config(&Auto, &Config::new().none_repr("-")).unwrap(),
+        "-"
+    );
+    assert_eq!(
+        serde_plain::to_string_with_config(&Auto, &Config::new().unit_struct_names(true)).unwrap(),
+        "Auto"
+    );
+}
+
+#[derive(Serialize)]
+pub enum Level {
+    Low,
+    Mid,
+    High,
+}
+
+#[test]
+fn test_variant_index() {
+    let config = Config::new().serialize_variant_index(true);
+    assert_eq!(serde_plain::to_string(&Level::High).unwrap(), "High");
+    assert_eq!(
+        serde_plain::to_string_with_config(&Level::Low, &config).unwrap(),
+        "0"
+    );
+    assert_eq!(
+        serde_plain::to_string_with_config(&Level::High, &config).unwrap(),
+        "2"
+    );
+    assert_eq!(
+        serde_plain::to_string_with_config(&Some(Level::Mid), &config).unwrap(),
+        "1"
+    );
+}
+
+#[test]
+fn test_serializer_values() {
+    use serde::Serialize;
+    use serde_plain::Serializer;
+
+    assert_eq!(42u32.serialize(Serializer).unwrap(), "42");
+    let config = Config::new().float_format(FloatFormat::Fixed(1));
+    assert_eq!(
+        2.0f64.serialize(Serializer::with_config(&config)).unwrap(),
+        "2.0"
+    );
+}
@@ -0,0 +1,95 @@
+#[macro_use]
+extern crate serde_derive;
+
+use serde_plain::{Config, EncodeSet};
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub enum Kind {
+    #[serde(rename = "a/b")]
+    Slashed,
+    Plain,
+}
+
+#[test]
+fn test_path_segment() {
+    let set = EncodeSet::PathSegment;
+    assert_eq!(
+        serde_plain::to_url_segment(&"café & co/2?#%", set).unwrap(),
+        "caf%C3%A9%20&%20co%2F2%3F%23%25"
+    );
+    assert_eq!(
+        serde_plain::to_url_segment(&"a+b=c;d,e:f@g~h", set).unwrap(),
+        "a+b=c;d,e:f@g~h"
+    );
+    assert_eq!(
+        serde_plain::to_url_segment(&Kind::Slashed, set).unwrap(),
+        "a%2Fb"
+    );
+    assert_eq!(
+        serde_plain::from_url_segment::<Kind>("a%2fb", set).unwrap(),
+        Kind::Slashed
+    );
+    assert_eq!(
+        serde_plain::from_url_segment::<String>("a+b", set).unwrap(),
+        "a+b"
+    );
+    assert_eq!(
+        serde_plain::from_url_segment::<String>("caf%C3%A9%20&%20co%2F2%3F%23%25", set).unwrap(),
+        "café & co/2?#%"
+    );
+}
+
+#[test]
+fn test_dot_segments() {
+    let set = EncodeSet::PathSegment;
+    assert_eq!(serde_plain::to_url_segment(&".", set).unwrap(), "%2E");
+    assert_eq!(serde_plain::to_url_segment(&"..", set).unwrap(), "%2E%2E");
+    assert_eq!(serde_plain::to_url_segment(&"...", set).unwrap(), "...");
+    assert_eq!(serde_plain::to_url_segment(&"a.b", set).unwrap(), "a.b");
+    assert_eq!(
+        serde_plain::from_url_segment::<String>("%2E%2E", set).unwrap(),
+        ".."
+    );
+    assert_eq!(
+        serde_plain::to_url_segment(&"..", EncodeSet::Query).unwrap(),
+        ".."
+    );
+}
+
+#[test]
+fn test_query() {
+    let set = EncodeSet::Query;
+    assert_eq!(
+        serde_plain::to_url_segment(&"a+b=c&d e/f?", set).unwrap(),
+        "a%2Bb%3Dc%26d%20e/f?"
+    );
+    assert_eq!(
+        serde_plain::from_url_segment::<String>("a%2Bb%3Dc%26d+e/f?", set).unwrap(),
+        "a+b=c&d e/f?"
+    );
+    assert_eq!(
+        serde_plain::from_url_segment::<i32>("-42", set).unwrap(),
+        -42
+    );
+
+    let config = Config::new().seq_separator(",");
+    let value = vec!["x y".to_string(), "z".to_string()];
+    let encoded = serde_plain::to_url_segment_with_config(&value, set, &config).unwrap();
+    assert_eq!(encoded, "x%20y,z");
+    let back: Vec<String> =
+        serde_plain::from_url_segment_with_config(&encoded, set, &config).unwrap();
+    assert_eq!(back, value);
+}
+
+#[test]
+fn test_invalid() {
+    let set = EncodeSet::PathSegment;
+    let err = serde_plain::from_url_segment::<String>("ab%2", set).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "cannot parse percent-encoding: invalid escape at offset 2"
+    );
+    assert!(serde_plain::from_url_segment::<String>("%zz", set).is_err());
+    assert!(serde_plain::from_url_segment::<String>("%FF", set).is_err());
+    assert!(serde_plain::from_url_segment::<u8>("300", set).is_err());
+}